@@ -3,10 +3,31 @@
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 use std::{
+    borrow::Borrow,
     cmp::Ordering,
-    ops::{Index, IndexMut},
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    iter::{FromIterator, Sum},
+    mem::ManuallyDrop,
+    ops::{Add, Index, IndexMut, Mul, Range, RangeBounds, Sub},
+    ptr::NonNull,
 };
 
+use crate::cursor::{Cursor, CursorMut};
+use crate::error::LengthMismatch;
+use crate::heap::{heapify, sift_down};
+use crate::iter::{
+    ChunkBy, Chunks, ChunksExact, ChunksMut, DedupWithCount, Deduped, EqFn, FoldWindows,
+    FoldWindowsIncremental, Gaps, IndexedIter, Iter, IterMut, Pairs, Positions, RChunks,
+    RChunksExact, Split, SplitInclusive, SplitN, Triples, WindowExtremes, Windows, WindowsStep,
+};
+use crate::merge::{check_merge_bounds, merge_buffered, merge_unbuffered, reverse, rotate};
+use crate::slice::{split_ranges, to_range, ArraySlice, ArraySliceMut, ChunkSplit, NthSplit};
+use crate::sort::{PivotStrategy, RandomPivot, SortOptions, SortSpec};
+
+/// Below this many remaining elements, binary searches switch to a linear scan.
+const LINEAR_SEARCH_THRESHOLD: usize = 8;
+
 /// Trait for data structures which have a length.
 pub trait HasLength {
     /// Return the length of the data structure.
@@ -32,11 +53,97 @@ pub trait Array: HasLength + Index<usize> {
         }
     }
 
+    /// Get a reference to the element at the given index without checking
+    /// that it's in bounds.
+    ///
+    /// The default implementation indexes the array, which checks the bounds
+    /// anyway. The sorting methods access elements through this method once
+    /// they've checked the bounds of the range they're sorting, so
+    /// implementors which can skip the check should override it.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`.
+    unsafe fn get_unchecked(&self, index: usize) -> &<Self as Index<usize>>::Output {
+        &self[index]
+    }
+
     /// Get a reference to the first element in the array.
     fn first(&self) -> Option<&<Self as Index<usize>>::Output> {
         self.get(0)
     }
 
+    /// Get a view of a range of the array.
+    ///
+    /// Panics if the range is out of bounds.
+    fn slice<R>(&self, range: R) -> ArraySlice<'_, Self>
+    where
+        R: RangeBounds<usize>,
+    {
+        ArraySlice::new(self, range)
+    }
+
+    /// Get a cursor positioned at the start of the array.
+    fn cursor(&self) -> Cursor<'_, Self> {
+        Cursor::new(self)
+    }
+
+    /// Split the array into `count` views of contiguous ranges whose lengths
+    /// differ by at most one.
+    ///
+    /// Exactly `count` views are returned, so some of them will be empty if
+    /// the array has fewer than `count` elements.
+    ///
+    /// Panics if `count` is zero.
+    fn split_n(&self, count: usize) -> Vec<ArraySlice<'_, Self>> {
+        if count == 0 {
+            panic!("Array::split_n: count cannot be zero!");
+        }
+        split_ranges(self.len(), count)
+            .map(|range| ArraySlice::new(self, range))
+            .collect()
+    }
+
+    /// Split the first element off the array, returning a reference to it
+    /// and a view of the rest of the array.
+    ///
+    /// Returns `None` if the array is empty.
+    fn split_first(&self) -> Option<(&<Self as Index<usize>>::Output, ArraySlice<'_, Self>)> {
+        let first = self.first()?;
+        Some((first, ArraySlice::new(self, 1..)))
+    }
+
+    /// Split the last element off the array, returning a reference to it and
+    /// a view of the rest of the array.
+    ///
+    /// Returns `None` if the array is empty.
+    fn split_last(&self) -> Option<(&<Self as Index<usize>>::Output, ArraySlice<'_, Self>)> {
+        let last = self.last()?;
+        Some((last, ArraySlice::new(self, ..self.len() - 1)))
+    }
+
+    /// Split the first `N` elements off the array, returning references to
+    /// them and a view of the rest of the array.
+    ///
+    /// Returns `None` if the array has fewer than `N` elements.
+    fn split_first_chunk<const N: usize>(&self) -> Option<ChunkSplit<'_, Self, N>> {
+        if self.len() < N {
+            return None;
+        }
+        let chunk = std::array::from_fn(|index| &self[index]);
+        Some((chunk, ArraySlice::new(self, N..)))
+    }
+
+    /// Split the last `N` elements off the array, returning references to them
+    /// and a view of the rest of the array.
+    ///
+    /// Returns `None` if the array has fewer than `N` elements.
+    fn split_last_chunk<const N: usize>(&self) -> Option<ChunkSplit<'_, Self, N>> {
+        let start = self.len().checked_sub(N)?;
+        let chunk = std::array::from_fn(|index| &self[start + index]);
+        Some((chunk, ArraySlice::new(self, ..start)))
+    }
+
     /// Get a reference to the last element in the array.
     fn last(&self) -> Option<&<Self as Index<usize>>::Output> {
         if self.is_empty() {
@@ -59,288 +166,4134 @@ pub trait Array: HasLength + Index<usize> {
         false
     }
 
-    /// Perform a binary search for `target`.
-    fn binary_search(&self, target: &<Self as Index<usize>>::Output) -> Result<usize, usize>
+    /// Return the index of the first element for which `predicate` returns
+    /// `true`, or `None` if there is no such element.
+    fn position<F>(&self, mut predicate: F) -> Option<usize>
     where
-        <Self as Index<usize>>::Output: Ord,
+        F: FnMut(&<Self as Index<usize>>::Output) -> bool,
     {
-        self.binary_search_by(|value| value.cmp(target))
+        (0..self.len()).find(|&index| predicate(&self[index]))
     }
 
-    /// Perform a binary search using a comparator function.
-    fn binary_search_by<F>(&self, mut compare: F) -> Result<usize, usize>
+    /// Combine every element of the array into a single value, from front to
+    /// back, using the function `f` and the initial value `init`.
+    fn fold<B, F>(&self, init: B, mut f: F) -> B
     where
-        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+        F: FnMut(B, &<Self as Index<usize>>::Output) -> B,
     {
-        let s = self;
-        let mut size = s.len();
-        if size == 0 {
-            return Err(0);
-        }
-        let mut base = 0usize;
-        while size > 1 {
-            let half = size / 2;
-            let mid = base + half;
-            let cmp = compare(&s[mid]);
-            base = if cmp == Ordering::Greater { base } else { mid };
-            size -= half;
+        let mut acc = init;
+        for index in 0..self.len() {
+            acc = f(acc, &self[index]);
         }
-        let cmp = compare(&s[base]);
-        if cmp == Ordering::Equal {
-            Ok(base)
-        } else {
-            Err(base + (cmp == Ordering::Less) as usize)
+        acc
+    }
+
+    /// Return the index of the first element which differs between this array
+    /// and `other`, or `None` if they're equal.
+    ///
+    /// If one array is a prefix of the other, the length of the shorter array
+    /// is returned.
+    fn mismatch<O>(&self, other: &O) -> Option<usize>
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: PartialEq,
+    {
+        let len = self.len().min(other.len());
+        match (0..len).find(|&index| self[index] != other[index]) {
+            None if self.len() == other.len() => None,
+            None => Some(len),
+            found => found,
         }
     }
 
-    /// Perform a binary search using a key and a key extractor function.
-    fn binary_search_by_key<K, F>(&self, key: &K, mut extract: F) -> Result<usize, usize>
+    /// Get an iterator over references to the elements of the array, from
+    /// front to back.
+    fn iter(&self) -> Iter<'_, Self> {
+        Iter::new(self)
+    }
+
+    /// Get an iterator over the elements of the array paired with their
+    /// indexes.
+    ///
+    /// Unlike `Iterator::enumerate`, the indexes stay correct when iterating
+    /// from the back.
+    fn indexed_iter(&self) -> IndexedIter<'_, Self> {
+        IndexedIter::new(self)
+    }
+
+    /// Get an iterator over the indexes of the elements for which `predicate`
+    /// returns `true`, in ascending order.
+    fn positions<F>(&self, predicate: F) -> Positions<'_, Self, F>
     where
-        F: FnMut(&<Self as Index<usize>>::Output) -> K,
-        K: Ord,
+        F: FnMut(&<Self as Index<usize>>::Output) -> bool,
     {
-        self.binary_search_by(|i| extract(i).cmp(key))
+        Positions::new(self, predicate)
     }
 
-    /// Test whether the array is sorted.
-    fn is_sorted(&self) -> bool
+    /// Get an iterator over the runs of consecutive equal elements in the
+    /// array, yielding the length of each run and a reference to its first
+    /// element.
+    fn iter_dedup_with_count(&self) -> DedupWithCount<'_, Self>
     where
-        <Self as Index<usize>>::Output: PartialOrd,
+        <Self as Index<usize>>::Output: PartialEq,
     {
-        self.is_sorted_by(|l, r| l.partial_cmp(r))
+        DedupWithCount::new(self)
     }
 
-    /// Test whether the array is sorted using a comparator function.
-    fn is_sorted_by<F>(&self, mut compare: F) -> bool
+    /// Get an iterator over references to the elements of the array, skipping
+    /// any element equal to the one before it.
+    fn iter_deduped(&self) -> Deduped<'_, Self, EqFn<<Self as Index<usize>>::Output>>
     where
-        F: FnMut(
-            &<Self as Index<usize>>::Output,
-            &<Self as Index<usize>>::Output,
-        ) -> Option<Ordering>,
+        <Self as Index<usize>>::Output: PartialEq,
     {
-        if self.len() < 2 {
-            true
-        } else {
-            for i in 1..self.len() {
-                if compare(&self[i - 1], &self[i]) == Some(Ordering::Greater) {
-                    return false;
-                }
-            }
-            true
-        }
+        Deduped::new(self, PartialEq::eq)
     }
 
-    /// Test whether the array is sorted using a key extractor function.
-    fn is_sorted_by_key<K, F>(&self, mut extract: F) -> bool
+    /// Get an iterator over references to the elements of the array, skipping
+    /// consecutive elements which `same_bucket` considers equal.
+    ///
+    /// `same_bucket` is called with each element and the last element yielded
+    /// before it, in that order.
+    fn iter_deduped_by<F>(&self, same_bucket: F) -> Deduped<'_, Self, F>
     where
-        F: FnMut(&<Self as Index<usize>>::Output) -> K,
-        K: PartialOrd<K>,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> bool,
     {
-        self.is_sorted_by(|l, r| extract(l).partial_cmp(&extract(r)))
+        Deduped::new(self, same_bucket)
     }
 
-    /// Test whether the array starts with the elements in `slice`.
-    fn starts_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    /// Get an iterator over the ranges of integers missing between
+    /// consecutive elements of a sorted array of integers.
+    ///
+    /// Integers before the first element and after the last aren't counted as
+    /// missing. The array must be sorted in ascending order, and may contain
+    /// duplicates. The `Ord` bound rules out floating point types, and the
+    /// `From<bool>` bound, which every primitive integer type meets, is how
+    /// the successor of an element is computed.
+    fn find_gaps(&self) -> Gaps<'_, Self>
     where
-        <Self as Index<usize>>::Output: PartialEq + Sized,
+        <Self as Index<usize>>::Output:
+            Copy + Ord + Add<Output = <Self as Index<usize>>::Output> + From<bool>,
     {
-        if slice.len() > self.len() {
-            return false;
-        }
-        for i in 0..slice.len() {
-            if self[i] != slice[i] {
-                return false;
-            }
-        }
-        true
+        Gaps::new(self)
     }
 
-    /// Test whether the array ends with the elements in `slice`.
-    fn ends_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    /// Get an iterator over the result of folding each window of length
+    /// `size` in the array, from front to back, starting from a clone of
+    /// `init` for each window.
+    ///
+    /// This calls `f` `size` times for every window. If the fold can be
+    /// updated incrementally, `fold_windows_incremental` is much faster.
+    ///
+    /// Panics if `size` is zero.
+    fn fold_windows<B, F>(&self, size: usize, init: B, f: F) -> FoldWindows<'_, Self, B, F>
     where
-        <Self as Index<usize>>::Output: PartialEq + Sized,
+        B: Clone,
+        F: FnMut(B, &<Self as Index<usize>>::Output) -> B,
     {
-        if slice.len() > self.len() {
-            return false;
-        }
-        let offset = self.len() - slice.len();
-        for i in 0..slice.len() {
-            if self[offset + i] != slice[i] {
-                return false;
-            }
-        }
-        true
+        FoldWindows::new(self, size, init, f)
     }
-}
 
-/// Trait for arrays with mutable indexes.
-pub trait ArrayMut: Array + IndexMut<usize> {
-    /// Get a mutable reference to the element at the given index.
-    fn get_mut(&mut self, index: usize) -> Option<&mut <Self as Index<usize>>::Output> {
-        if index >= self.len() {
-            None
-        } else {
-            Some(&mut self[index])
-        }
+    /// Get an iterator over a running accumulator for each window of length
+    /// `size` in the array, from front to back.
+    ///
+    /// As the window slides along the array, `remove` is called with each
+    /// element leaving the window and `add` with each element entering it,
+    /// so that the whole traversal takes O(n) time. This suits moving sums
+    /// and averages.
+    ///
+    /// Panics if `size` is zero.
+    fn fold_windows_incremental<B, Add, Remove>(
+        &self,
+        size: usize,
+        init: B,
+        add: Add,
+        remove: Remove,
+    ) -> FoldWindowsIncremental<'_, Self, B, Add, Remove>
+    where
+        B: Clone,
+        Add: FnMut(&mut B, &<Self as Index<usize>>::Output),
+        Remove: FnMut(&mut B, &<Self as Index<usize>>::Output),
+    {
+        FoldWindowsIncremental::new(self, size, init, add, remove)
     }
 
-    /// Get a mutable reference to the first element in the array.
-    fn first_mut(&mut self) -> Option<&mut <Self as Index<usize>>::Output> {
-        self.get_mut(0)
+    /// Get an iterator over views of every window of length `size` in the
+    /// array, from front to back. The windows overlap.
+    ///
+    /// If the array is shorter than `size`, the iterator yields nothing.
+    ///
+    /// Panics if `size` is zero.
+    fn windows(&self, size: usize) -> Windows<'_, Self> {
+        Windows::new(self, size)
     }
 
-    /// Get a mutable reference to the last element in the array.
-    fn last_mut(&mut self) -> Option<&mut <Self as Index<usize>>::Output> {
-        if self.is_empty() {
-            None
-        } else {
-            self.get_mut(self.len() - 1)
-        }
+    /// Get an iterator over views of non-overlapping chunks of length `size`
+    /// in the array, starting at the front.
+    ///
+    /// If the array's length isn't a multiple of `size`, the last chunk is
+    /// shorter.
+    ///
+    /// Panics if `size` is zero.
+    fn chunks(&self, size: usize) -> Chunks<'_, Self> {
+        Chunks::new(self, size)
     }
 
-    /// Set the value of the element at the given index.
+    /// Get an iterator over views of non-overlapping chunks of exactly
+    /// `size` elements in the array, starting at the front.
     ///
-    /// Returns the previous value, or `None` if the index is out of bounds.
-    fn set(
-        &mut self,
-        index: usize,
-        value: <Self as Index<usize>>::Output,
-    ) -> Option<<Self as Index<usize>>::Output>
-    where
-        <Self as Index<usize>>::Output: Sized,
-    {
-        self.get_mut(index).map(|p| std::mem::replace(p, value))
+    /// If the array's length isn't a multiple of `size`, the elements left
+    /// over at the end aren't yielded, but can be retrieved with
+    /// `ChunksExact::remainder`.
+    ///
+    /// Panics if `size` is zero.
+    fn chunks_exact(&self, size: usize) -> ChunksExact<'_, Self> {
+        ChunksExact::new(self, size)
     }
 
-    /// Swap the elements at two indexes.
-    fn swap(&mut self, index1: usize, index2: usize)
-    where
-        <Self as Index<usize>>::Output: Sized,
-    {
-        if index1 != index2 {
-            let ptr1: *mut <Self as Index<usize>>::Output = &mut self[index1];
-            let ptr2: *mut <Self as Index<usize>>::Output = &mut self[index2];
-            unsafe { std::ptr::swap(ptr1, ptr2) };
-        }
+    /// Get an iterator over views of non-overlapping chunks of length `size`
+    /// in the array, starting at the back.
+    ///
+    /// If the array's length isn't a multiple of `size`, the last chunk,
+    /// which is taken from the front of the array, is shorter.
+    ///
+    /// Panics if `size` is zero.
+    fn rchunks(&self, size: usize) -> RChunks<'_, Self> {
+        RChunks::new(self, size)
     }
 
-    /// Get mutable references to the elements at two indexes and call a function on them.
+    /// Get an iterator over views of non-overlapping chunks of exactly
+    /// `size` elements in the array, starting at the back.
     ///
-    /// This provides a safe way to get two mutable references into an array at the same time,
-    /// which would normally be disallowed by the borrow checker.
-    fn map_pair<F, A>(&mut self, index1: usize, index2: usize, mut f: F) -> A
+    /// If the array's length isn't a multiple of `size`, the elements left
+    /// over at the front aren't yielded, but can be retrieved with
+    /// `RChunksExact::remainder`.
+    ///
+    /// Panics if `size` is zero.
+    fn rchunks_exact(&self, size: usize) -> RChunksExact<'_, Self> {
+        RChunksExact::new(self, size)
+    }
+
+    /// Get an iterator over views of the parts of the array separated by
+    /// elements for which `predicate` returns `true`.
+    ///
+    /// The matching elements aren't included in any part. As with
+    /// `slice::split`, adjacent matches produce an empty part between them,
+    /// and matches at either end produce an empty part at that end.
+    fn split<P>(&self, predicate: P) -> Split<'_, Self, P>
     where
-        F: FnMut(&mut <Self as Index<usize>>::Output, &mut <Self as Index<usize>>::Output) -> A,
+        P: FnMut(&<Self as Index<usize>>::Output) -> bool,
     {
-        if index1 == index2 {
-            panic!("ArrayMut::map_pair: indices cannot be equal!");
-        }
-        let pa: *mut <Self as Index<usize>>::Output = self.index_mut(index1);
-        let pb: *mut <Self as Index<usize>>::Output = self.index_mut(index2);
-        unsafe { f(&mut *pa, &mut *pb) }
+        Split::new(self, predicate)
     }
 
-    /// Sort the elements of the array.
-    fn sort_unstable(&mut self)
+    /// Get an iterator over views of the parts of the array separated by
+    /// elements for which `predicate` returns `true`, yielding at most
+    /// `count` parts.
+    ///
+    /// The last part yielded contains the rest of the array, including any
+    /// matching elements in it.
+    fn splitn<P>(&self, count: usize, predicate: P) -> SplitN<'_, Self, P>
     where
-        <Self as Index<usize>>::Output: Ord + Sized,
+        P: FnMut(&<Self as Index<usize>>::Output) -> bool,
     {
-        self.sort_unstable_by(|l, r| l.cmp(r))
+        SplitN::new(self, count, predicate)
     }
 
-    /// Sort the elements of the array using a comparator function.
-    fn sort_unstable_by<F>(&mut self, mut compare: F)
+    /// Get an iterator over views of the parts of the array separated by
+    /// elements for which `predicate` returns `true`, with each matching
+    /// element included at the end of the part it ends.
+    ///
+    /// Unlike `split`, a match at the end of the array doesn't produce an
+    /// empty part after it, and an empty array produces no parts at all.
+    fn split_inclusive<P>(&self, predicate: P) -> SplitInclusive<'_, Self, P>
     where
-        <Self as Index<usize>>::Output: Sized,
-        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+        P: FnMut(&<Self as Index<usize>>::Output) -> bool,
     {
-        crate::sort::quicksort(self, 0, self.len() - 1, |a, b| compare(a, b));
+        SplitInclusive::new(self, predicate)
     }
 
-    /// Sort the elements of the array using a key extractor function.
-    fn sort_unstable_by_key<F, K>(&mut self, mut extract: F)
+    /// Get an iterator over views of the longest runs of adjacent elements
+    /// for which `predicate` returns `true`.
+    ///
+    /// `predicate` is called with each pair of adjacent elements, and a new
+    /// run starts wherever it returns `false`. Chunking by `==` yields runs
+    /// of equal elements, for instance.
+    fn chunk_by<P>(&self, predicate: P) -> ChunkBy<'_, Self, P>
     where
-        F: FnMut(&<Self as Index<usize>>::Output) -> K,
-        K: Ord,
-        <Self as Index<usize>>::Output: Sized,
+        P: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> bool,
     {
-        self.sort_unstable_by(|l, r| extract(l).cmp(&extract(r)))
+        ChunkBy::new(self, predicate)
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use std::iter::FromIterator;
-
-    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-    struct TestVec<A>(Vec<A>);
+    /// Get an iterator over views of the windows of length `size` in the
+    /// array, starting every `step` elements.
+    ///
+    /// Windows overlap if `step` is less than `size`, and elements are
+    /// skipped if it's greater. Elements at the end of the array which don't
+    /// fill a whole window are left out, unless `WindowsStep::with_partial` is
+    /// called on the iterator.
+    ///
+    /// Panics if `size` or `step` is zero.
+    fn windows_step(&self, size: usize, step: usize) -> WindowsStep<'_, Self> {
+        WindowsStep::new(self, size, step)
+    }
 
-    impl<A> HasLength for TestVec<A> {
-        fn len(&self) -> usize {
-            self.0.len()
-        }
+    /// Get an iterator over each pair of adjacent elements in the array.
+    fn pairs(&self) -> Pairs<'_, Self> {
+        Pairs::new(self)
     }
 
-    impl<A> Index<usize> for TestVec<A> {
-        type Output = A;
-        fn index(&self, index: usize) -> &A {
-            &self.0[index]
-        }
+    /// Get an iterator over each triple of adjacent elements in the array.
+    fn triples(&self) -> Triples<'_, Self> {
+        Triples::new(self)
     }
 
-    impl<A> IndexMut<usize> for TestVec<A> {
-        fn index_mut(&mut self, index: usize) -> &mut A {
-            &mut self.0[index]
-        }
+    /// Get an iterator over the smallest element of each window of length
+    /// `size` in the array, from front to back.
+    ///
+    /// This keeps a queue of candidate minimums as the window slides along
+    /// the array, so that the whole traversal takes O(n) time regardless of
+    /// `size`. If a window contains several equally small elements, the first
+    /// of them is returned.
+    ///
+    /// Panics if `size` is zero.
+    fn window_min(&self, size: usize) -> WindowExtremes<'_, Self>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        WindowExtremes::new(self, size, Ordering::Less)
     }
 
-    impl<A> Array for TestVec<A> {}
-    impl<A> ArrayMut for TestVec<A> {}
+    /// Get an iterator over the largest element of each window of length
+    /// `size` in the array, from front to back.
+    ///
+    /// See `window_min`.
+    ///
+    /// Panics if `size` is zero.
+    fn window_max(&self, size: usize) -> WindowExtremes<'_, Self>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        WindowExtremes::new(self, size, Ordering::Greater)
+    }
 
-    impl<A> FromIterator<A> for TestVec<A> {
-        fn from_iter<I>(iter: I) -> Self
-        where
-            I: IntoIterator<Item = A>,
-        {
-            Self(Vec::from_iter(iter))
-        }
+    /// Perform a binary search for `target`.
+    fn binary_search(&self, target: &<Self as Index<usize>>::Output) -> Result<usize, usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.binary_search_by(|value| value.cmp(target))
     }
 
-    impl<A> From<Vec<A>> for TestVec<A> {
-        fn from(vec: Vec<A>) -> Self {
-            Self(vec)
-        }
+    /// Perform a binary search for a borrowed form of the element type, like
+    /// `HashMap::get`.
+    ///
+    /// This lets you search an array of `String`s for a `&str`, for
+    /// instance, without allocating a `String` to compare against.
+    fn binary_search_borrowed<Q>(&self, target: &Q) -> Result<usize, usize>
+    where
+        <Self as Index<usize>>::Output: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.binary_search_by(|value| value.borrow().cmp(target))
+    }
+
+    /// Perform a binary search for `target` in an array sorted in descending
+    /// order.
+    fn binary_search_desc(&self, target: &<Self as Index<usize>>::Output) -> Result<usize, usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.binary_search_by(|value| target.cmp(value))
+    }
+
+    /// Perform a binary search using a comparator function.
+    ///
+    /// Once the search has narrowed down to a handful of elements, the
+    /// remaining elements are scanned in order rather than bisected further.
+    fn binary_search_by<F>(&self, mut compare: F) -> Result<usize, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        #[cfg(all(feature = "validate-ordering", debug_assertions))]
+        crate::validate::check_search(self, &mut compare);
+        let s = self;
+        let mut size = s.len();
+        let mut base = 0usize;
+        while size > LINEAR_SEARCH_THRESHOLD {
+            let half = size / 2;
+            let mid = base + half;
+            let cmp = compare(&s[mid]);
+            base = if cmp == Ordering::Greater { base } else { mid };
+            size -= half;
+        }
+        // Scanning the last few elements in order is cheaper than continuing
+        // to bisect them when indexing is expensive.
+        for index in base..base + size {
+            match compare(&s[index]) {
+                Ordering::Less => {}
+                Ordering::Equal => return Ok(index),
+                Ordering::Greater => return Err(index),
+            }
+        }
+        Err(base + size)
+    }
+
+    /// Perform an exponential search for `target`.
+    ///
+    /// Like `binary_search`, this returns `Ok` with an index of a matching
+    /// element, or `Err` with the index where `target` could be inserted. If
+    /// several elements match, it may return a different one of them than
+    /// `binary_search` does. It checks the elements at indexes 1, 2, 4, 8 and
+    /// so on until it passes `target`, and then only binary searches that
+    /// bracket. This takes O(log i) comparisons, where `i` is the index of
+    /// `target`, so it's faster than a binary search when `target` is likely
+    /// to be near the front of a large array.
+    fn exponential_search(&self, target: &<Self as Index<usize>>::Output) -> Result<usize, usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.exponential_search_by(|value| value.cmp(target))
+    }
+
+    /// Perform an exponential search using a comparator function.
+    ///
+    /// See `exponential_search`.
+    fn exponential_search_by<F>(&self, mut compare: F) -> Result<usize, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let len = self.len();
+        let mut bound = 1;
+        while bound < len && compare(&self[bound]) == Ordering::Less {
+            bound *= 2;
+        }
+        let start = bound / 2;
+        let end = len.min(bound + 1);
+        match ArraySlice::new(self, start..end).binary_search_by(compare) {
+            Ok(index) => Ok(start + index),
+            Err(index) => Err(start + index),
+        }
+    }
+
+    /// Perform a binary search for `target`, starting from the index `hint`.
+    ///
+    /// Like `binary_search`, this returns `Ok` with an index of a matching
+    /// element, or `Err` with the index where `target` could be inserted. If
+    /// several elements match, it may return a different one of them than
+    /// `binary_search` does. It gallops outwards from `hint` in steps of 1, 2,
+    /// 4, 8 and so on to bracket `target` before binary searching the bracket.
+    /// This takes O(log d) comparisons, where `d` is the distance between
+    /// `hint` and the index of `target`, so it's much faster than a plain
+    /// binary search when looking up keys close to each other, for instance
+    /// by passing the result of the previous lookup as the hint. A `hint` past
+    /// the end of the array is treated as pointing at the last element.
+    fn binary_search_with_hint(
+        &self,
+        target: &<Self as Index<usize>>::Output,
+        hint: usize,
+    ) -> Result<usize, usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.binary_search_with_hint_by(hint, |value| value.cmp(target))
+    }
+
+    /// Perform a binary search from the index `hint` using a comparator
+    /// function.
+    ///
+    /// See `binary_search_with_hint`.
+    fn binary_search_with_hint_by<F>(&self, hint: usize, mut compare: F) -> Result<usize, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let len = self.len();
+        if len == 0 {
+            return Err(0);
+        }
+        let hint = hint.min(len - 1);
+        let (start, end) = match compare(&self[hint]) {
+            Ordering::Equal => return Ok(hint),
+            Ordering::Less => {
+                let mut start = hint + 1;
+                let mut step = 1;
+                while hint + step < len && compare(&self[hint + step]) == Ordering::Less {
+                    start = hint + step + 1;
+                    step *= 2;
+                }
+                (start, len.min(hint + step + 1))
+            }
+            Ordering::Greater => {
+                let mut end = hint;
+                let mut step = 1;
+                while step <= hint && compare(&self[hint - step]) == Ordering::Greater {
+                    end = hint - step;
+                    step *= 2;
+                }
+                (hint.saturating_sub(step), end)
+            }
+        };
+        match ArraySlice::new(self, start..end).binary_search_by(compare) {
+            Ok(index) => Ok(start + index),
+            Err(index) => Err(start + index),
+        }
+    }
+
+    /// Search for `target` in an array which was sorted in ascending order and
+    /// then rotated, as happens to the storage of a ring buffer, returning
+    /// the index of a matching element if there is one.
+    ///
+    /// The rotation point is found with a binary search too, so this takes
+    /// O(log n) time, although it can degrade towards O(n) when there are
+    /// many duplicates of the smallest or largest elements.
+    fn binary_search_rotated(&self, target: &<Self as Index<usize>>::Output) -> Option<usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        // Find the index of the smallest element, where the rotation starts.
+        let mut low = 0;
+        let mut high = self.len() - 1;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self[mid].cmp(&self[high]) {
+                Ordering::Greater => low = mid + 1,
+                Ordering::Less => high = mid,
+                Ordering::Equal if self[high - 1] > self[high] => {
+                    low = high;
+                    break;
+                }
+                Ordering::Equal => high -= 1,
+            }
+        }
+        let (start, end) = if low == 0 || target < &self[0] {
+            (low, self.len())
+        } else {
+            (0, low)
+        };
+        ArraySlice::new(self, start..end)
+            .binary_search(target)
+            .ok()
+            .map(|index| start + index)
+    }
+
+    /// Perform a binary search using a key and a key extractor function.
+    fn binary_search_by_key<K, F>(&self, key: &K, mut extract: F) -> Result<usize, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+    {
+        self.binary_search_by(|i| extract(i).cmp(key))
+    }
+
+    /// Perform a binary search for each of `keys` using a key extractor
+    /// function, returning the results in the same order as `keys`.
+    ///
+    /// The keys are sorted, which takes O(k log k) time for k keys, and
+    /// searched for in ascending order. Each search is a full binary search
+    /// over the part of the array after where the previous one ended, so the
+    /// searches are narrowed monotonically, but each still takes O(log n)
+    /// time.
+    ///
+    /// With the `validate-ordering` feature enabled in a debug build, each of
+    /// these searches checks the ordering of the part of the array it covers
+    /// separately.
+    fn binary_search_many<K, F>(&self, keys: &[K], mut extract: F) -> Vec<Result<usize, usize>>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+    {
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_unstable_by(|&left, &right| keys[left].cmp(&keys[right]));
+        let mut results = vec![Err(0); keys.len()];
+        let mut start = 0;
+        for index in order {
+            let key = &keys[index];
+            let result = match ArraySlice::new(self, start..)
+                .binary_search_by(|value| extract(value).cmp(key))
+            {
+                Ok(found) => Ok(start + found),
+                Err(insert) => Err(start + insert),
+            };
+            start = match result {
+                Ok(found) | Err(found) => found,
+            };
+            results[index] = result;
+        }
+        results
+    }
+
+    /// Find the index of the first element for which `predicate` returns
+    /// `false`, in an array which is partitioned so that every element for
+    /// which it returns `true` comes before every element for which it
+    /// returns `false`.
+    ///
+    /// If every element matches, the length of the array is returned. If the
+    /// array isn't partitioned, the result is unspecified. This is a binary
+    /// search, so it takes O(log n) time.
+    fn partition_point<P>(&self, mut predicate: P) -> usize
+    where
+        P: FnMut(&<Self as Index<usize>>::Output) -> bool,
+    {
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if predicate(&self[mid]) {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        low
+    }
+
+    /// Find the first index at which `value` could be inserted into a sorted
+    /// array while keeping it sorted, which is the index of the first
+    /// element not less than `value`.
+    fn lower_bound(&self, value: &<Self as Index<usize>>::Output) -> usize
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.partition_point(|element| element < value)
+    }
+
+    /// Find the last index at which `value` could be inserted into a sorted
+    /// array while keeping it sorted, which is the index of the first
+    /// element greater than `value`.
+    fn upper_bound(&self, value: &<Self as Index<usize>>::Output) -> usize
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.partition_point(|element| element <= value)
+    }
+
+    /// Find the range of elements equal to `value` in a sorted array.
+    ///
+    /// If there are no such elements, the empty range at the index where
+    /// `value` could be inserted is returned.
+    fn equal_range(&self, value: &<Self as Index<usize>>::Output) -> Range<usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.equal_range_by(|element| element.cmp(value))
+    }
+
+    /// Find the range of elements for which `compare` returns
+    /// `Ordering::Equal` in a sorted array, using a comparator function as
+    /// for `binary_search_by`.
+    fn equal_range_by<F>(&self, mut compare: F) -> Range<usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let start = self.partition_point(|element| compare(element) == Ordering::Less);
+        let end = start
+            + ArraySlice::new(self, start..)
+                .partition_point(|element| compare(element) != Ordering::Greater);
+        start..end
+    }
+
+    /// Find the range of elements with the key `key` in an array sorted by
+    /// key.
+    fn equal_range_by_key<K, F>(&self, key: &K, mut extract: F) -> Range<usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+    {
+        self.equal_range_by(|element| extract(element).cmp(key))
+    }
+
+    /// Find the index at which `value` could be inserted into a sorted array
+    /// while keeping it sorted.
+    ///
+    /// If the array contains elements equal to `value`, the index of any one
+    /// of them may be returned.
+    fn insertion_index(&self, value: &<Self as Index<usize>>::Output) -> usize
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.insertion_index_by(|element| element.cmp(value))
+    }
+
+    /// Find the index at which an element could be inserted into a sorted
+    /// array while keeping it sorted, using a comparator function as for
+    /// `binary_search_by`.
+    fn insertion_index_by<F>(&self, compare: F) -> usize
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        match self.binary_search_by(compare) {
+            Ok(index) | Err(index) => index,
+        }
+    }
+
+    /// Find the index at which an element with the key `key` could be
+    /// inserted into an array sorted by key while keeping it sorted.
+    fn insertion_index_by_key<K, F>(&self, key: &K, extract: F) -> usize
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+    {
+        match self.binary_search_by_key(key, extract) {
+            Ok(index) | Err(index) => index,
+        }
+    }
+
+    /// Test whether the array is sorted.
+    fn is_sorted(&self) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialOrd,
+    {
+        self.is_sorted_by_cmp(|l, r| l.partial_cmp(r))
+    }
+
+    /// Test whether the array is sorted in descending order.
+    fn is_sorted_desc(&self) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialOrd,
+    {
+        self.is_sorted_by_cmp(|l, r| r.partial_cmp(l))
+    }
+
+    /// Test whether the array is sorted using a predicate which tests
+    /// whether two elements are in order.
+    ///
+    /// `is_ordered` is called with each pair of adjacent elements, and the
+    /// array is sorted if it returns `true` for every pair. This matches
+    /// `slice::is_sorted_by` in the standard library.
+    fn is_sorted_by<F>(&self, mut is_ordered: F) -> bool
+    where
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> bool,
+    {
+        (1..self.len()).all(|i| is_ordered(&self[i - 1], &self[i]))
+    }
+
+    /// Test whether the array is sorted using a comparator function.
+    ///
+    /// Only pairs of adjacent elements for which `compare` returns
+    /// `Some(Ordering::Greater)` count as out of order, so elements which
+    /// are incomparable, like NaN, don't make an array unsorted.
+    fn is_sorted_by_cmp<F>(&self, mut compare: F) -> bool
+    where
+        F: FnMut(
+            &<Self as Index<usize>>::Output,
+            &<Self as Index<usize>>::Output,
+        ) -> Option<Ordering>,
+    {
+        if self.len() < 2 {
+            true
+        } else {
+            for i in 1..self.len() {
+                if compare(&self[i - 1], &self[i]) == Some(Ordering::Greater) {
+                    return false;
+                }
+            }
+            true
+        }
+    }
+
+    /// Test whether the array is sorted using a key extractor function.
+    fn is_sorted_by_key<K, F>(&self, mut extract: F) -> bool
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: PartialOrd<K>,
+    {
+        self.is_sorted_by_cmp(|l, r| extract(l).partial_cmp(&extract(r)))
+    }
+
+    /// Test whether the array starts with the elements in `slice`.
+    fn starts_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        if slice.len() > self.len() {
+            return false;
+        }
+        for i in 0..slice.len() {
+            if self[i] != slice[i] {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Test whether the array ends with the elements in `slice`.
+    fn ends_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        if slice.len() > self.len() {
+            return false;
+        }
+        let offset = self.len() - slice.len();
+        for i in 0..slice.len() {
+            if self[offset + i] != slice[i] {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Test whether the array starts with the elements yielded by `prefix`.
+    ///
+    /// Unlike `starts_with`, this works for arrays whose elements are
+    /// unsized.
+    fn starts_with_iter<'a, I>(&self, prefix: I) -> bool
+    where
+        I: IntoIterator<Item = &'a <Self as Index<usize>>::Output>,
+        <Self as Index<usize>>::Output: PartialEq + 'a,
+    {
+        for (index, value) in prefix.into_iter().enumerate() {
+            if index == self.len() || &self[index] != value {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Test whether the array ends with the elements yielded by `suffix`.
+    ///
+    /// Unlike `ends_with`, this works for arrays whose elements are unsized.
+    fn ends_with_iter<'a, I>(&self, suffix: I) -> bool
+    where
+        I: IntoIterator<Item = &'a <Self as Index<usize>>::Output>,
+        I::IntoIter: DoubleEndedIterator,
+        <Self as Index<usize>>::Output: PartialEq + 'a,
+    {
+        let mut index = self.len();
+        for value in suffix.into_iter().rev() {
+            if index == 0 || &self[index - 1] != value {
+                return false;
+            }
+            index -= 1;
+        }
+        true
+    }
+
+    /// Test whether the array starts with the elements in `slice`, using
+    /// `eq` to compare elements.
+    fn starts_with_by<B, F>(&self, slice: &[B], mut eq: F) -> bool
+    where
+        F: FnMut(&<Self as Index<usize>>::Output, &B) -> bool,
+    {
+        slice.len() <= self.len() && (0..slice.len()).all(|i| eq(&self[i], &slice[i]))
+    }
+
+    /// Test whether the array ends with the elements in `slice`, using `eq`
+    /// to compare elements.
+    fn ends_with_by<B, F>(&self, slice: &[B], mut eq: F) -> bool
+    where
+        F: FnMut(&<Self as Index<usize>>::Output, &B) -> bool,
+    {
+        if slice.len() > self.len() {
+            return false;
+        }
+        let offset = self.len() - slice.len();
+        (0..slice.len()).all(|i| eq(&self[offset + i], &slice[i]))
+    }
+
+    /// Test whether this array and `other` have the same length and equal
+    /// elements, using `eq` to compare elements.
+    fn eq_by<O, F>(&self, other: &O, mut eq: F) -> bool
+    where
+        O: Array + ?Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<O as Index<usize>>::Output) -> bool,
+    {
+        self.len() == other.len() && (0..self.len()).all(|i| eq(&self[i], &other[i]))
+    }
+
+    /// Test whether the elements of this array appear in `other` in the same
+    /// order, though not necessarily next to each other.
+    ///
+    /// This takes O(n + m) time.
+    fn is_subsequence_of<O>(&self, other: &O) -> bool
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: PartialEq,
+    {
+        let mut index = 0;
+        for other_index in 0..other.len() {
+            if index == self.len() {
+                break;
+            }
+            if self[index] == other[other_index] {
+                index += 1;
+            }
+        }
+        index == self.len()
+    }
+
+    /// Compute the dot product of this array and `other`.
+    ///
+    /// Panics if the arrays are of different lengths.
+    fn dot<O>(&self, other: &O) -> <Self as Index<usize>>::Output
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Clone
+            + Mul<Output = <Self as Index<usize>>::Output>
+            + Sum<<Self as Index<usize>>::Output>,
+    {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "Array::dot: arrays must have equal lengths!"
+        );
+        (0..self.len())
+            .map(|index| self[index].clone() * other[index].clone())
+            .sum()
+    }
+
+    /// Clone the elements of the array into a fixed size array.
+    ///
+    /// Returns an error carrying the array's actual length if it isn't
+    /// exactly `N`.
+    fn try_into_array<const N: usize>(
+        &self,
+    ) -> Result<[<Self as Index<usize>>::Output; N], LengthMismatch>
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        if self.len() != N {
+            return Err(LengthMismatch {
+                expected: N,
+                actual: self.len(),
+            });
+        }
+        Ok(std::array::from_fn(|index| self[index].clone()))
+    }
+
+    /// Split an array of pairs into two arrays, by cloning the first and
+    /// second element of each pair into `left` and `right` respectively.
+    ///
+    /// Panics if `left` and `right` don't have the same length as this array.
+    fn unzip_into<A, B, L, R>(&self, left: &mut L, right: &mut R)
+    where
+        Self: Index<usize, Output = (A, B)>,
+        A: Clone,
+        B: Clone,
+        L: ArrayMut + IndexMut<usize, Output = A> + ?Sized,
+        R: ArrayMut + IndexMut<usize, Output = B> + ?Sized,
+    {
+        assert_eq!(
+            self.len(),
+            left.len(),
+            "Array::unzip_into: arrays must have equal lengths!"
+        );
+        assert_eq!(
+            self.len(),
+            right.len(),
+            "Array::unzip_into: arrays must have equal lengths!"
+        );
+        for index in 0..self.len() {
+            let (a, b) = &self[index];
+            left[index] = a.clone();
+            right[index] = b.clone();
+        }
+    }
+
+    /// Split an array of pairs into two new collections, by cloning the first
+    /// and second element of each pair.
+    fn unzip<A, B, L, R>(&self) -> (L, R)
+    where
+        Self: Index<usize, Output = (A, B)>,
+        A: Clone,
+        B: Clone,
+        L: FromIterator<A>,
+        R: FromIterator<B>,
+    {
+        let left = (0..self.len()).map(|index| self[index].0.clone()).collect();
+        let right = (0..self.len()).map(|index| self[index].1.clone()).collect();
+        (left, right)
+    }
+
+    /// Clone the elements of this array and `other` alternately into `out`,
+    /// starting with this array.
+    ///
+    /// Panics if `other` isn't the same length as this array, or if `out`
+    /// isn't exactly twice that length.
+    fn interleave_into<O, D>(&self, other: &O, out: &mut D)
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        D: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Clone,
+    {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "Array::interleave_into: arrays must have equal lengths!"
+        );
+        assert_eq!(
+            self.len() * 2,
+            out.len(),
+            "Array::interleave_into: destination must be twice the length of the source arrays!"
+        );
+        for index in 0..self.len() {
+            out[index * 2] = self[index].clone();
+            out[index * 2 + 1] = other[index].clone();
+        }
+    }
+
+    /// Clone the elements of this array and `other` alternately into a new
+    /// collection, starting with this array.
+    ///
+    /// Panics if `other` isn't the same length as this array.
+    fn interleave<O, C>(&self, other: &O) -> C
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        C: FromIterator<<Self as Index<usize>>::Output>,
+        <Self as Index<usize>>::Output: Clone,
+    {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "Array::interleave: arrays must have equal lengths!"
+        );
+        (0..self.len() * 2)
+            .map(|index| {
+                if index % 2 == 0 {
+                    self[index / 2].clone()
+                } else {
+                    other[index / 2].clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Clone the elements of this array into `out` in sorted order, leaving
+    /// this array untouched.
+    ///
+    /// This sorts a list of indexes into the array rather than the elements
+    /// themselves, so it works on arrays which can't be mutated, and each
+    /// element is cloned exactly once. The sort is stable.
+    ///
+    /// Panics if `out` isn't the same length as this array.
+    fn sorted_copy_into<D>(&self, out: &mut D)
+    where
+        D: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Ord + Clone,
+    {
+        self.sorted_copy_into_by(out, Ord::cmp)
+    }
+
+    /// Clone the elements of this array into `out` in the order defined by a
+    /// comparator function, leaving this array untouched.
+    ///
+    /// See `sorted_copy_into`.
+    fn sorted_copy_into_by<D, F>(&self, out: &mut D, compare: F)
+    where
+        D: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Clone,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        assert_eq!(
+            self.len(),
+            out.len(),
+            "Array::sorted_copy_into: arrays must have equal lengths!"
+        );
+        let indexes = crate::sort::sorted_indexes(self, compare, true);
+        for (target, source) in indexes.into_iter().enumerate() {
+            out[target] = self[source].clone();
+        }
+    }
+
+    /// Clone the elements of this array into a new collection in sorted order,
+    /// leaving this array untouched.
+    ///
+    /// See `sorted_copy_into`.
+    fn sorted_copy<C>(&self) -> C
+    where
+        C: FromIterator<<Self as Index<usize>>::Output>,
+        <Self as Index<usize>>::Output: Ord + Clone,
+    {
+        crate::sort::sorted_indexes(self, Ord::cmp, true)
+            .into_iter()
+            .map(|index| self[index].clone())
+            .collect()
+    }
+
+    /// Find the longest subsequence of the array whose elements are strictly
+    /// increasing, and return the indexes of its elements in ascending order.
+    ///
+    /// If there are several longest increasing subsequences, one of them is
+    /// returned. This uses patience sorting, and takes O(n log n) time.
+    fn longest_increasing_subsequence(&self) -> Vec<usize>
+    where
+        <Self as Index<usize>>::Output: Ord,
+    {
+        self.longest_increasing_subsequence_by(Ord::cmp)
+    }
+
+    /// Find the longest subsequence of the array whose elements are strictly
+    /// increasing according to a comparator function, and return the indexes
+    /// of its elements in ascending order.
+    fn longest_increasing_subsequence_by<F>(&self, mut compare: F) -> Vec<usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        // `tails[length - 1]` is the index of the smallest element which ends
+        // an increasing subsequence of that length, and `previous[index]` is
+        // the index before `index` in the subsequence it ends.
+        let mut tails: Vec<usize> = Vec::new();
+        let mut previous = vec![0; self.len()];
+        for index in 0..self.len() {
+            let length =
+                tails.partition_point(|&tail| compare(&self[tail], &self[index]) == Ordering::Less);
+            if length > 0 {
+                previous[index] = tails[length - 1];
+            }
+            if length == tails.len() {
+                tails.push(index);
+            } else {
+                tails[length] = index;
+            }
+        }
+        let mut result = vec![0; tails.len()];
+        if let Some(&last) = tails.last() {
+            let mut index = last;
+            for slot in result.iter_mut().rev() {
+                *slot = index;
+                index = previous[index];
+            }
+        }
+        result
+    }
+
+    /// Find the longest subsequence of the array whose elements' keys are
+    /// strictly increasing, and return the indexes of its elements in
+    /// ascending order.
+    fn longest_increasing_subsequence_by_key<K, F>(&self, mut extract: F) -> Vec<usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+    {
+        self.longest_increasing_subsequence_by(|l, r| extract(l).cmp(&extract(r)))
+    }
+
+    /// Collect clones of the first occurrence of each distinct element of the
+    /// array, in their original order, into a new collection.
+    ///
+    /// Unlike `ArrayResize::dedup_with_count`, this removes duplicates which
+    /// aren't next to each other, so the array needn't be sorted.
+    fn deduped<C>(&self) -> C
+    where
+        C: FromIterator<<Self as Index<usize>>::Output>,
+        <Self as Index<usize>>::Output: Hash + Eq + Clone,
+    {
+        let mut seen = HashSet::new();
+        (0..self.len())
+            .filter(|&index| seen.insert(&self[index]))
+            .map(|index| self[index].clone())
+            .collect()
+    }
+
+    /// Collect the indexes of the array's elements into a map, grouped by the
+    /// key `extract` returns for each element.
+    ///
+    /// The indexes in each group are in ascending order.
+    fn group_indices_by_key<K, F>(&self, mut extract: F) -> HashMap<K, Vec<usize>>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Hash + Eq,
+    {
+        let mut groups: HashMap<K, Vec<usize>> = HashMap::new();
+        for index in 0..self.len() {
+            groups.entry(extract(&self[index])).or_default().push(index);
+        }
+        groups
+    }
+
+    /// Count the occurrences of each distinct element in the array.
+    fn counts(&self) -> HashMap<<Self as Index<usize>>::Output, usize>
+    where
+        <Self as Index<usize>>::Output: Hash + Eq + Clone,
+    {
+        self.counts_by(Clone::clone)
+    }
+
+    /// Count the number of elements in the array for each distinct key
+    /// `extract` returns.
+    fn counts_by<K, F>(&self, mut extract: F) -> HashMap<K, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Hash + Eq,
+    {
+        self.fold(HashMap::new(), |mut counts, value| {
+            *counts.entry(extract(value)).or_insert(0) += 1;
+            counts
+        })
+    }
+
+    /// Find the most frequent element in the array.
+    ///
+    /// If several elements are equally frequent, the one which occurs first
+    /// in the array is returned. Returns `None` if the array is empty.
+    fn mode(&self) -> Option<&<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Hash + Eq,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let mut counts: HashMap<&<Self as Index<usize>>::Output, usize> = HashMap::new();
+        for index in 0..self.len() {
+            *counts.entry(&self[index]).or_insert(0) += 1;
+        }
+        let (mut mode, mut best) = (0, 0);
+        for index in 0..self.len() {
+            let count = counts[&self[index]];
+            if count > best {
+                mode = index;
+                best = count;
+            }
+        }
+        Some(&self[mode])
+    }
+
+    /// Find the element which makes up more than half of the array, if there
+    /// is one.
+    ///
+    /// This uses the Boyer-Moore majority vote algorithm, which takes two
+    /// passes over the array and constant space.
+    fn majority_element(&self) -> Option<&<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: PartialEq,
+    {
+        let mut candidate = 0;
+        let mut votes = 0;
+        for index in 0..self.len() {
+            if votes == 0 {
+                candidate = index;
+                votes = 1;
+            } else if self[index] == self[candidate] {
+                votes += 1;
+            } else {
+                votes -= 1;
+            }
+        }
+        if votes == 0 {
+            return None;
+        }
+        let candidate = &self[candidate];
+        let count = self.fold(
+            0,
+            |count, value| {
+                if value == candidate {
+                    count + 1
+                } else {
+                    count
+                }
+            },
+        );
+        if count > self.len() / 2 {
+            Some(candidate)
+        } else {
+            None
+        }
+    }
+
+    /// Fill `out` with clones of the smallest elements of the array, in
+    /// ascending order, and return how many were written.
+    ///
+    /// As many elements are found as `out` has room for, or the whole array
+    /// if it's shorter than `out`, in which case the rest of `out` is left
+    /// untouched. The selection runs in O(n log k) time using a heap kept in
+    /// `out` itself, so it doesn't allocate.
+    fn k_smallest_into<O>(&self, out: &mut O) -> usize
+    where
+        O: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Ord + Clone,
+    {
+        self.k_smallest_into_by(out, Ord::cmp)
+    }
+
+    /// Fill `out` with clones of the largest elements of the array, in
+    /// descending order, and return how many were written.
+    ///
+    /// See `k_smallest_into`.
+    fn k_largest_into<O>(&self, out: &mut O) -> usize
+    where
+        O: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Ord + Clone,
+    {
+        self.k_smallest_into_by(out, |left, right| right.cmp(left))
+    }
+
+    /// Fill `out` with clones of the smallest elements of the array according
+    /// to a comparator function, in ascending order, and return how many were
+    /// written.
+    ///
+    /// See `k_smallest_into`.
+    fn k_smallest_into_by<O, F>(&self, out: &mut O, mut compare: F) -> usize
+    where
+        O: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Clone,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let count = out.len().min(self.len());
+        if count == 0 {
+            return 0;
+        }
+        for index in 0..count {
+            out[index] = self[index].clone();
+        }
+        // `out` holds a max-heap of the smallest elements seen so far, whose
+        // root is replaced whenever a smaller element turns up.
+        heapify(out, count, &mut compare);
+        for index in count..self.len() {
+            if compare(&self[index], &out[0]) == Ordering::Less {
+                out[0] = self[index].clone();
+                sift_down(out, 0, count, &mut compare);
+            }
+        }
+        for end in (1..count).rev() {
+            out.swap(0, end);
+            sift_down(out, 0, end, &mut compare);
+        }
+        count
+    }
+}
+
+/// Mutable references to two different elements of an array, as returned by
+/// `ArrayMut::get2_mut`.
+pub type PairMut<'a, Arr> = (
+    &'a mut <Arr as Index<usize>>::Output,
+    &'a mut <Arr as Index<usize>>::Output,
+);
+
+/// Trait for arrays with mutable indexes.
+pub trait ArrayMut: Array + IndexMut<usize> {
+    /// Get a mutable reference to the element at the given index.
+    fn get_mut(&mut self, index: usize) -> Option<&mut <Self as Index<usize>>::Output> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(&mut self[index])
+        }
+    }
+
+    /// Get mutable references to the elements at `N` indexes at once.
+    ///
+    /// Returns `None` if any index is out of bounds, or if any two indexes
+    /// are equal.
+    fn get_many_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Option<[&mut <Self as Index<usize>>::Output; N]>
+    where
+        Self: DisjointIndexMut,
+    {
+        let len = self.len();
+        for (position, &index) in indices.iter().enumerate() {
+            if index >= len || indices[..position].contains(&index) {
+                return None;
+            }
+        }
+        let array: *mut Self = self;
+        // The indexes are distinct and in bounds, so `DisjointIndexMut`
+        // promises the references don't overlap, and they all borrow `self`
+        // mutably.
+        Some(std::array::from_fn(|position| unsafe {
+            &mut *Self::get_unchecked_ptr_mut(array, indices[position])
+        }))
+    }
+
+    /// Get a mutable reference to the first element in the array.
+    fn first_mut(&mut self) -> Option<&mut <Self as Index<usize>>::Output> {
+        self.get_mut(0)
+    }
+
+    /// Get a mutable reference to the element at the given index without
+    /// checking that it's in bounds.
+    ///
+    /// The default implementation indexes the array, which checks the bounds
+    /// anyway. See `Array::get_unchecked`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `self.len()`.
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut <Self as Index<usize>>::Output {
+        &mut self[index]
+    }
+
+    /// Get a pointer to the element at the given index from a pointer to the
+    /// array, without checking that it's in bounds.
+    ///
+    /// This is how views and iterators which share a pointer to an array
+    /// reach its elements. The default implementation goes through
+    /// `get_unchecked_mut`, which creates a mutable reference to the whole
+    /// array, so arrays implementing `DisjointIndexMut` must override it. See
+    /// the safety requirements there.
+    ///
+    /// # Safety
+    ///
+    /// `this` must point to a live array, and `index` must be less than its
+    /// length. Unless the array implements `DisjointIndexMut`, no other
+    /// reference to the array may be alive.
+    unsafe fn get_unchecked_ptr_mut(
+        this: *mut Self,
+        index: usize,
+    ) -> *mut <Self as Index<usize>>::Output {
+        (*this).get_unchecked_mut(index)
+    }
+
+    /// Get a mutable view of a range of the array.
+    ///
+    /// Panics if the range is out of bounds.
+    fn slice_mut<R>(&mut self, range: R) -> ArraySliceMut<'_, Self>
+    where
+        R: RangeBounds<usize>,
+    {
+        ArraySliceMut::new(self, range)
+    }
+
+    /// Get an iterator over mutable references to the elements of the array,
+    /// from front to back.
+    fn iter_mut(&mut self) -> IterMut<'_, Self>
+    where
+        Self: DisjointIndexMut,
+    {
+        IterMut::new(self)
+    }
+
+    /// Get an iterator over mutable views of non-overlapping chunks of length
+    /// `size` in the array, starting at the front.
+    ///
+    /// The views never overlap, so they can be held at the same time, and
+    /// handed out to different workers. If the array's length isn't a
+    /// multiple of `size`, the last chunk is shorter.
+    ///
+    /// Panics if `size` is zero.
+    fn chunks_mut(&mut self, size: usize) -> ChunksMut<'_, Self>
+    where
+        Self: DisjointIndexMut,
+    {
+        ChunksMut::new(self, size)
+    }
+
+    /// Get a mutable cursor positioned at the start of the array.
+    fn cursor_mut(&mut self) -> CursorMut<'_, Self> {
+        CursorMut::new(self)
+    }
+
+    /// Split the array into `count` mutable views of disjoint, contiguous
+    /// ranges whose lengths differ by at most one.
+    ///
+    /// Exactly `count` views are returned, so some of them will be empty if
+    /// the array has fewer than `count` elements.
+    ///
+    /// Panics if `count` is zero.
+    fn split_n_mut(&mut self, count: usize) -> Vec<ArraySliceMut<'_, Self>>
+    where
+        Self: DisjointIndexMut,
+    {
+        if count == 0 {
+            panic!("ArrayMut::split_n_mut: count cannot be zero!");
+        }
+        let array = NonNull::from(self);
+        // The ranges are disjoint, all the views borrow `self` mutably, and
+        // `DisjointIndexMut` lets them index the array at the same time.
+        split_ranges(unsafe { array.as_ref() }.len(), count)
+            .map(|range| unsafe { ArraySliceMut::from_raw(array, range) })
+            .collect()
+    }
+
+    /// Get a mutable reference to the last element in the array.
+    fn last_mut(&mut self) -> Option<&mut <Self as Index<usize>>::Output> {
+        if self.is_empty() {
+            None
+        } else {
+            self.get_mut(self.len() - 1)
+        }
+    }
+
+    /// Set the value of the element at the given index.
+    ///
+    /// Returns the previous value, or `None` if the index is out of bounds.
+    fn set(
+        &mut self,
+        index: usize,
+        value: <Self as Index<usize>>::Output,
+    ) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.get_mut(index).map(|p| std::mem::replace(p, value))
+    }
+
+    /// Overwrite the elements starting at `offset` with clones of `values`.
+    ///
+    /// Panics if the range is out of bounds, before writing any elements.
+    fn set_range(&mut self, offset: usize, values: &[<Self as Index<usize>>::Output])
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        self.set_range_from_iter(offset, values.iter().cloned())
+    }
+
+    /// Overwrite the elements starting at `offset` with the values produced
+    /// by an iterator.
+    ///
+    /// Panics if the range is out of bounds, before writing any elements.
+    fn set_range_from_iter<I>(&mut self, offset: usize, values: I)
+    where
+        I: IntoIterator<Item = <Self as Index<usize>>::Output>,
+        I::IntoIter: ExactSizeIterator,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let values = values.into_iter();
+        let len = self.len();
+        if offset > len || values.len() > len - offset {
+            panic!(
+                "ArrayMut::set_range: range of length {} at offset {} out of bounds for length {}",
+                values.len(),
+                offset,
+                len
+            );
+        }
+        for (index, value) in (offset..).zip(values) {
+            self[index] = value;
+        }
+    }
+
+    /// Replace the element at the given index with `value`, returning the
+    /// previous element.
+    ///
+    /// This is the same as `set`, named after `Option::replace`. Returns
+    /// `None` if the index is out of bounds.
+    fn replace(
+        &mut self,
+        index: usize,
+        value: <Self as Index<usize>>::Output,
+    ) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.set(index, value)
+    }
+
+    /// Move the element at the given index out of the array, leaving the
+    /// default value in its place.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    fn take(&mut self, index: usize) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Default,
+    {
+        self.get_mut(index).map(std::mem::take)
+    }
+
+    /// Call a function with a mutable reference to the element at the given
+    /// index, returning its result.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    fn update<F, R>(&mut self, index: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut <Self as Index<usize>>::Output) -> R,
+    {
+        self.get_mut(index).map(f)
+    }
+
+    /// Replace the element at the given index with the result of calling a
+    /// function on it.
+    ///
+    /// The element is replaced with its default value while `f` runs, and
+    /// the default value is left in its place if `f` panics.
+    ///
+    /// Returns `false` if the index is out of bounds.
+    fn set_with<F>(&mut self, index: usize, f: F) -> bool
+    where
+        F: FnOnce(<Self as Index<usize>>::Output) -> <Self as Index<usize>>::Output,
+        <Self as Index<usize>>::Output: Default,
+    {
+        match self.get_mut(index) {
+            Some(element) => {
+                *element = f(std::mem::take(element));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Swap the elements at two indexes.
+    fn swap(&mut self, index1: usize, index2: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if index1 >= len || index2 >= len {
+            panic!(
+                "ArrayMut::swap: index out of bounds: the len is {} but the indices are {} and {}",
+                len, index1, index2
+            );
+        }
+        unsafe { self.swap_unchecked(index1, index2) }
+    }
+
+    /// Swap the elements at two indexes without checking that they're in
+    /// bounds.
+    ///
+    /// # Safety
+    ///
+    /// Both indexes must be less than `self.len()`.
+    unsafe fn swap_unchecked(&mut self, index1: usize, index2: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        if index1 == index2 {
+            return;
+        }
+        if let Some((value1, value2)) = self.get2_mut(index1, index2) {
+            std::mem::swap(value1, value2);
+            return;
+        }
+        // Each pointer is taken from `get_unchecked_mut` right before it's
+        // used, because getting another one may invalidate it if `index_mut`
+        // borrows the whole storage mutably. Until the last write, the array
+        // still holds bitwise copies of whatever is out of it, so a
+        // copy-on-write array unsharing its storage only ever clones intact
+        // elements.
+        let value1 = ManuallyDrop::new(std::ptr::read(self.get_unchecked_mut(index1)));
+        let value2 = ManuallyDrop::new(std::ptr::read(self.get_unchecked_mut(index2)));
+        std::ptr::write(
+            self.get_unchecked_mut(index1),
+            ManuallyDrop::into_inner(value2),
+        );
+        // The second slot still holds a copy of the element now in the first,
+        // so if getting the last pointer panics, the guard puts the first
+        // element back where it came from.
+        let mut guard: PairGuard<'_, Self> = PairGuard {
+            array: self,
+            index1,
+            index2,
+            value1: Some(ManuallyDrop::into_inner(value1)),
+            value2: None,
+        };
+        let hole: *mut <Self as Index<usize>>::Output = guard.array.get_unchecked_mut(index2);
+        if let Some(value) = guard.value1.take() {
+            std::ptr::write(hole, value);
+        }
+    }
+
+    /// Get mutable references to the elements at two different indexes at
+    /// the same time.
+    ///
+    /// Returns `None` if either index is out of bounds or they're equal, or
+    /// if the array can't hand out two mutable references into itself at
+    /// once, which is what the default implementation assumes. Arrays which
+    /// can, such as `VecDeque`, should override it, which lets `swap` and
+    /// `map_pair` borrow the elements in place instead of moving them out of
+    /// the array.
+    fn get2_mut(&mut self, _index1: usize, _index2: usize) -> Option<PairMut<'_, Self>> {
+        None
+    }
+
+    /// Get mutable references to the elements at two indexes and call a function on them.
+    ///
+    /// This provides a safe way to get two mutable references into an array at the same time,
+    /// which would normally be disallowed by the borrow checker.
+    ///
+    /// The default implementation borrows the elements through `get2_mut` if
+    /// the array supports it. Otherwise, it moves both elements out of the
+    /// array for the duration of the call and moves them back afterwards,
+    /// even if `f` panics, so that `f` never holds mutable borrows into the
+    /// array itself.
+    fn map_pair<F, A>(&mut self, index1: usize, index2: usize, mut f: F) -> A
+    where
+        F: FnMut(&mut <Self as Index<usize>>::Output, &mut <Self as Index<usize>>::Output) -> A,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        if index1 == index2 {
+            panic!("ArrayMut::map_pair: indices cannot be equal!");
+        }
+        let len = self.len();
+        if index1 >= len || index2 >= len {
+            panic!(
+                "ArrayMut::map_pair: index out of bounds: the len is {} but the indices are {} and {}",
+                len, index1, index2
+            );
+        }
+        if let Some((value1, value2)) = self.get2_mut(index1, index2) {
+            return f(value1, value2);
+        }
+        // See `swap_unchecked`.
+        let value1 = ManuallyDrop::new(unsafe { std::ptr::read(&mut self[index1] as *mut _) });
+        let value2 = ManuallyDrop::new(unsafe { std::ptr::read(&mut self[index2] as *mut _) });
+        let mut guard: PairGuard<'_, Self> = PairGuard {
+            array: self,
+            index1,
+            index2,
+            value1: Some(ManuallyDrop::into_inner(value1)),
+            value2: Some(ManuallyDrop::into_inner(value2)),
+        };
+        let result = match (&mut guard.value1, &mut guard.value2) {
+            (Some(value1), Some(value2)) => f(value1, value2),
+            _ => unreachable!(),
+        };
+        guard.fill();
+        result
+    }
+
+    /// Sort the elements of the array, keeping equal elements in their
+    /// original order.
+    ///
+    /// This is a merge sort, which allocates a scratch buffer to merge runs
+    /// of elements through. If you don't need the stability guarantee,
+    /// `sort_unstable` is faster and doesn't allocate.
+    fn sort(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.sort_by(Ord::cmp)
+    }
+
+    /// Stably sort the elements of the array using a comparator function.
+    ///
+    /// See `sort`. If `compare` panics, the array will still contain all of
+    /// its original elements, in an unspecified order.
+    fn sort_by<F>(&mut self, compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        crate::sort::merge_sort(self, compare)
+    }
+
+    /// Stably sort the elements of the array using a key extractor function.
+    ///
+    /// See `sort`.
+    fn sort_by_key<F, K>(&mut self, mut extract: F)
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.sort_by(|l, r| extract(l).cmp(&extract(r)))
+    }
+
+    /// Sort the elements of the array.
+    fn sort_unstable(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.sort_unstable_by(|l, r| l.cmp(r))
+    }
+
+    /// Sort the elements of the array in descending order.
+    fn sort_unstable_desc(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.sort_unstable_by(|l, r| r.cmp(l))
+    }
+
+    /// Sort the elements of the array using a comparator function.
+    ///
+    /// If `compare` panics, the array will still contain all of its original
+    /// elements, in an unspecified order.
+    fn sort_unstable_by<F>(&mut self, mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        if self.len() > 1 {
+            crate::sort::quicksort(self, 0, self.len() - 1, |a, b| compare(a, b));
+        }
+    }
+
+    /// Sort the elements of the array by a sort order made of several keys.
+    ///
+    /// This sort is stable: elements which are equal by every key keep their
+    /// original order.
+    fn sort_with_spec(&mut self, spec: SortSpec<'_, <Self as Index<usize>>::Output>)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        crate::sort::sort_by_permutation(self, |left, right| spec.compare(left, right), true)
+    }
+
+    /// Sort the elements of the array by sorting a list of their indexes
+    /// first, and then moving each element directly to its final position.
+    ///
+    /// This moves each element at most once, unlike `sort_unstable`, which
+    /// swaps elements around many times. It needs to allocate a `Vec` of
+    /// indexes, but it's much faster for arrays of large elements, or arrays
+    /// whose elements are expensive to move.
+    fn sort_unstable_indirect(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.sort_unstable_indirect_by(Ord::cmp)
+    }
+
+    /// Sort the elements of the array indirectly using a comparator function.
+    ///
+    /// See `sort_unstable_indirect`.
+    fn sort_unstable_indirect_by<F>(&mut self, compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        crate::sort::sort_by_permutation(self, compare, false)
+    }
+
+    /// Sort the elements of the array with heapsort.
+    ///
+    /// Heapsort takes O(n log n) time for any input and never allocates, but
+    /// it's usually slower than `sort_unstable`, which already falls back
+    /// to it when its pivots are chosen badly. It's mostly useful where
+    /// a hard bound on running time matters more than average speed.
+    fn heapsort(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.heapsort_by(Ord::cmp)
+    }
+
+    /// Sort the elements of the array with heapsort using a comparator
+    /// function.
+    ///
+    /// See `heapsort`.
+    fn heapsort_by<F>(&mut self, mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        #[cfg(all(feature = "validate-ordering", debug_assertions))]
+        let mut compare = crate::validate::comparator(&mut compare);
+        crate::heap::heapsort(self, &mut compare)
+    }
+
+    /// Sort the elements of the array with heapsort using a key extractor
+    /// function.
+    ///
+    /// See `heapsort`.
+    fn heapsort_by_key<F, K>(&mut self, mut extract: F)
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.heapsort_by(|l, r| extract(l).cmp(&extract(r)))
+    }
+
+    /// Sort the elements of the array while writing to it as few times as
+    /// possible.
+    ///
+    /// This uses cycle sort, which moves each element directly to its final
+    /// position, writing only to positions whose elements change. It makes
+    /// O(n²) comparisons, so it's only worth using when writes are far more
+    /// expensive than reads, as with arrays backed by flash memory.
+    fn sort_unstable_min_writes(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Clone,
+    {
+        self.sort_unstable_min_writes_by(Ord::cmp)
+    }
+
+    /// Sort the elements of the array using a comparator function while
+    /// writing to it as few times as possible.
+    ///
+    /// See `sort_unstable_min_writes`.
+    fn sort_unstable_min_writes_by<F>(&mut self, compare: F)
+    where
+        <Self as Index<usize>>::Output: Clone,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        crate::sort::cycle_sort(self, compare)
+    }
+
+    /// Sort the elements of the array using a comparator function and a
+    /// custom strategy for choosing quicksort pivots.
+    ///
+    /// `sort_unstable_by` uses `RandomPivot`, which is a safe choice for any
+    /// input, but a different strategy may suit particular data or
+    /// containers better.
+    fn sort_unstable_with<P, F>(&mut self, mut strategy: P, mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        P: PivotStrategy,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        if self.len() > 1 {
+            crate::sort::quicksort_with(
+                self,
+                0,
+                self.len() - 1,
+                |a, b| compare(a, b),
+                &mut strategy,
+            );
+        }
+    }
+
+    /// Sort the elements of the array using a comparator function, with the
+    /// sorting algorithm and its parameters chosen at runtime by `options`.
+    ///
+    /// With the default options, this is the same as `sort_unstable_by`.
+    fn sort_with_options<F>(&mut self, options: SortOptions, compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        crate::sort::sort_with_options(self, options, compare)
+    }
+
+    /// Sort the elements of the array using a key extractor function.
+    fn sort_unstable_by_key<F, K>(&mut self, mut extract: F)
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.sort_unstable_by(|l, r| extract(l).cmp(&extract(r)))
+    }
+
+    /// Sort the elements of the array using a key extractor function, calling
+    /// it only once for each element.
+    ///
+    /// The keys are computed up front into a scratch buffer, which is sorted,
+    /// and the elements are then moved directly to their final positions.
+    /// This is much faster than `sort_unstable_by_key` when the key is
+    /// expensive to compute, but for cheap keys the extra allocation makes it
+    /// slower.
+    fn sort_unstable_by_cached_key<F, K>(&mut self, extract: F)
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        crate::sort::sort_by_cached_key(self, extract)
+    }
+
+    /// Sort the elements of the array using a key extractor function which
+    /// returns a reference into the element.
+    ///
+    /// Unlike `sort_unstable_by_key`, this lets the key borrow from the
+    /// element, such as a `&str` field of a struct, so it needn't be cloned
+    /// for every comparison.
+    fn sort_unstable_by_ref_key<F, K>(&mut self, mut extract: F)
+    where
+        F: for<'a> FnMut(&'a <Self as Index<usize>>::Output) -> &'a K,
+        K: Ord + ?Sized,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.sort_unstable_by(|l, r| extract(l).cmp(extract(r)))
+    }
+
+    /// Replace each element with its difference from the previous element.
+    ///
+    /// The first element is left unchanged. This is the inverse of a prefix
+    /// sum, and is useful for delta encoding a sequence.
+    fn adjacent_difference(&mut self)
+    where
+        <Self as Index<usize>>::Output: Clone + Sub<Output = <Self as Index<usize>>::Output>,
+    {
+        for index in (1..self.len()).rev() {
+            let value = self[index].clone() - self[index - 1].clone();
+            self[index] = value;
+        }
+    }
+
+    /// Replace each element with its difference from the previous element,
+    /// using `seed` as the element preceding the first.
+    fn adjacent_difference_from(&mut self, seed: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Clone + Sub<Output = <Self as Index<usize>>::Output>,
+    {
+        self.adjacent_difference();
+        if let Some(first) = self.first_mut() {
+            *first = first.clone() - seed;
+        }
+    }
+
+    /// Overwrite every element with a clone of `value`.
+    fn fill(&mut self, value: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        for index in 0..self.len() {
+            self[index] = value.clone();
+        }
+    }
+
+    /// Overwrite every element with the result of calling `f`.
+    fn fill_with<F>(&mut self, mut f: F)
+    where
+        F: FnMut() -> <Self as Index<usize>>::Output,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        for index in 0..self.len() {
+            self[index] = f();
+        }
+    }
+
+    /// Overwrite every element with a clone of the element at the same index
+    /// in `source`.
+    ///
+    /// Panics if `source` doesn't have the same length as the array.
+    fn clone_from_slice(&mut self, source: &[<Self as Index<usize>>::Output])
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        let len = self.len();
+        if source.len() != len {
+            panic!(
+                "ArrayMut::clone_from_slice: source length {} does not match array length {}",
+                source.len(),
+                len
+            );
+        }
+        for (index, value) in source.iter().enumerate() {
+            self[index] = value.clone();
+        }
+    }
+
+    /// Overwrite every element with a copy of the element at the same index
+    /// in `source`.
+    ///
+    /// Panics if `source` doesn't have the same length as the array.
+    fn copy_from_slice(&mut self, source: &[<Self as Index<usize>>::Output])
+    where
+        <Self as Index<usize>>::Output: Copy,
+    {
+        let len = self.len();
+        if source.len() != len {
+            panic!(
+                "ArrayMut::copy_from_slice: source length {} does not match array length {}",
+                source.len(),
+                len
+            );
+        }
+        for (index, value) in source.iter().enumerate() {
+            self[index] = *value;
+        }
+    }
+
+    /// Copy the elements in `source` to the range starting at `dest`.
+    ///
+    /// The two ranges may overlap: the elements are copied in an order which
+    /// ensures each source element is read before it's overwritten.
+    ///
+    /// Panics if either range is out of bounds.
+    fn copy_within<R>(&mut self, source: R, dest: usize)
+    where
+        R: RangeBounds<usize>,
+        <Self as Index<usize>>::Output: Copy,
+    {
+        let len = self.len();
+        let source = to_range(&source, len);
+        let count = source.len();
+        if dest > len - count {
+            panic!(
+                "ArrayMut::copy_within: destination {} out of bounds for length {}",
+                dest, len
+            );
+        }
+        if dest <= source.start {
+            for index in 0..count {
+                self[dest + index] = self[source.start + index];
+            }
+        } else {
+            for index in (0..count).rev() {
+                self[dest + index] = self[source.start + index];
+            }
+        }
+    }
+
+    /// Swap every element with the element at the same index in `other`.
+    ///
+    /// Panics if `other` doesn't have the same length as the array.
+    fn swap_with_slice(&mut self, other: &mut [<Self as Index<usize>>::Output])
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if other.len() != len {
+            panic!(
+                "ArrayMut::swap_with_slice: slice length {} does not match array length {}",
+                other.len(),
+                len
+            );
+        }
+        for (index, value) in other.iter_mut().enumerate() {
+            std::mem::swap(&mut self[index], value);
+        }
+    }
+
+    /// Swap every element with the element at the same index in another
+    /// array.
+    ///
+    /// Panics if `other` doesn't have the same length as the array.
+    fn swap_with_array<Other>(&mut self, other: &mut Other)
+    where
+        Other: ArrayMut + IndexMut<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if other.len() != len {
+            panic!(
+                "ArrayMut::swap_with_array: other length {} does not match array length {}",
+                other.len(),
+                len
+            );
+        }
+        for index in 0..len {
+            std::mem::swap(&mut self[index], &mut other[index]);
+        }
+    }
+
+    /// Overwrite every element with the result of calling `f` on its index.
+    fn fill_with_index<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize) -> <Self as Index<usize>>::Output,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        for index in 0..self.len() {
+            self[index] = f(index);
+        }
+    }
+
+    /// Reverse the order of the elements in the array, in place.
+    fn reverse(&mut self)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        reverse(self, 0, self.len());
+    }
+
+    /// Reverse the order of the elements in `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    fn reverse_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let range = to_range(&range, self.len());
+        reverse(self, range.start, range.end);
+    }
+
+    /// Rotate the elements in `range` so that the element `count` positions
+    /// into the range moves to its start, and the first `count` elements
+    /// move to its end.
+    ///
+    /// Panics if the range is out of bounds, or if `count` is greater than
+    /// the length of the range.
+    fn rotate_range<R>(&mut self, range: R, count: usize)
+    where
+        R: RangeBounds<usize>,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let range = to_range(&range, self.len());
+        if count > range.len() {
+            panic!(
+                "ArrayMut::rotate_range: count {} is greater than the length {} of the range",
+                count,
+                range.len()
+            );
+        }
+        rotate(self, range.start, range.start + count, range.end);
+    }
+
+    /// Rotate the array in place so that the first `mid` elements move to
+    /// the end, and the element at index `mid` becomes the first.
+    ///
+    /// Panics if `mid` is greater than the length of the array.
+    fn rotate_left(&mut self, mid: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if mid > len {
+            panic!(
+                "ArrayMut::rotate_left: mid {} is greater than length {}",
+                mid, len
+            );
+        }
+        rotate(self, 0, mid, len);
+    }
+
+    /// Rotate the array in place so that the last `k` elements move to the
+    /// front, and the element at index `len - k` becomes the first.
+    ///
+    /// Panics if `k` is greater than the length of the array.
+    fn rotate_right(&mut self, k: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if k > len {
+            panic!(
+                "ArrayMut::rotate_right: k {} is greater than length {}",
+                k, len
+            );
+        }
+        rotate(self, 0, len - k, len);
+    }
+
+    /// Move every `Some` element to the front of an array of `Option`s,
+    /// preserving their order, and return how many there are.
+    ///
+    /// The `None` elements are left at the end of the array.
+    fn compact<T>(&mut self) -> usize
+    where
+        Self: IndexMut<usize, Output = Option<T>>,
+    {
+        let mut write = 0;
+        for read in 0..self.len() {
+            if self[read].is_some() {
+                if read != write {
+                    self.swap(write, read);
+                }
+                write += 1;
+            }
+        }
+        write
+    }
+
+    /// Move the element at index `from` to index `to`, shifting the elements
+    /// in between by one position to make room and preserving their order.
+    ///
+    /// Panics if either index is out of bounds.
+    fn move_element(&mut self, from: usize, to: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        if from >= len || to >= len {
+            panic!(
+                "ArrayMut::move_element: index out of bounds: the len is {} but the indices are {} and {}",
+                len, from, to
+            );
+        }
+        if from < to {
+            rotate(self, from, from + 1, to + 1);
+        } else if from > to {
+            rotate(self, to, from, from + 1);
+        }
+    }
+
+    /// Move every element `count` positions towards the start of the array,
+    /// dropping the first `count` elements and filling the vacated positions
+    /// at the end with clones of `fill`.
+    ///
+    /// If `count` is greater than the length of the array, every element is
+    /// replaced.
+    fn shift_left(&mut self, count: usize, fill: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        self.shift_left_with(count, || fill.clone())
+    }
+
+    /// Move every element `count` positions towards the start of the array,
+    /// dropping the first `count` elements and filling the vacated positions
+    /// at the end with the results of calling `fill`.
+    ///
+    /// Pass `Default::default` to fill with default values.
+    fn shift_left_with<F>(&mut self, count: usize, mut fill: F)
+    where
+        F: FnMut() -> <Self as Index<usize>>::Output,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        let count = count.min(len);
+        for index in count..len {
+            self.swap(index - count, index);
+        }
+        for index in len - count..len {
+            self[index] = fill();
+        }
+    }
+
+    /// Move every element `count` positions towards the end of the array,
+    /// dropping the last `count` elements and filling the vacated positions
+    /// at the start with clones of `fill`.
+    ///
+    /// If `count` is greater than the length of the array, every element is
+    /// replaced.
+    fn shift_right(&mut self, count: usize, fill: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Clone,
+    {
+        self.shift_right_with(count, || fill.clone())
+    }
+
+    /// Move every element `count` positions towards the end of the array,
+    /// dropping the last `count` elements and filling the vacated positions
+    /// at the start with the results of calling `fill`.
+    ///
+    /// Pass `Default::default` to fill with default values.
+    fn shift_right_with<F>(&mut self, count: usize, mut fill: F)
+    where
+        F: FnMut() -> <Self as Index<usize>>::Output,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        let len = self.len();
+        let count = count.min(len);
+        for index in (count..len).rev() {
+            self.swap(index - count, index);
+        }
+        for index in 0..count {
+            self[index] = fill();
+        }
+    }
+
+    /// Restrict every element to the interval `[min, max]`.
+    ///
+    /// Elements less than `min` are replaced with `min`, and elements greater
+    /// than `max` are replaced with `max`. Elements which are incomparable with
+    /// the bounds, such as `NaN`, are left as they are.
+    ///
+    /// Panics if `min > max`.
+    fn clamp_all(
+        &mut self,
+        min: &<Self as Index<usize>>::Output,
+        max: &<Self as Index<usize>>::Output,
+    ) where
+        <Self as Index<usize>>::Output: PartialOrd + Clone,
+    {
+        assert!(
+            min.partial_cmp(max) != Some(Ordering::Greater),
+            "ArrayMut::clamp_all: min cannot be greater than max!"
+        );
+        for index in 0..self.len() {
+            let value = &mut self[index];
+            if *value < *min {
+                *value = min.clone();
+            } else if *value > *max {
+                *value = max.clone();
+            }
+        }
+    }
+
+    /// Call a function on each element of this array and the element at the
+    /// same index in `other`, allowing the function to update this array's
+    /// element in place.
+    ///
+    /// Panics if the arrays are of different lengths.
+    fn zip_apply<O, F>(&mut self, other: &O, mut f: F)
+    where
+        O: Array + ?Sized,
+        F: FnMut(&mut <Self as Index<usize>>::Output, &<O as Index<usize>>::Output),
+    {
+        assert_eq!(
+            self.len(),
+            other.len(),
+            "ArrayMut::zip_apply: arrays must have equal lengths!"
+        );
+        for index in 0..self.len() {
+            f(&mut self[index], &other[index]);
+        }
+    }
+
+    /// Call a function with a mutable view of each window of length `size`
+    /// in the array, from front to back.
+    ///
+    /// The windows overlap, so changes made through one window are visible
+    /// to the windows following it. If the array is shorter than `size`, the
+    /// function is never called.
+    ///
+    /// Panics if `size` is zero.
+    fn for_each_window_mut<F>(&mut self, size: usize, mut f: F)
+    where
+        F: FnMut(&mut ArraySliceMut<'_, Self>),
+    {
+        if size == 0 {
+            panic!("ArrayMut::for_each_window_mut: window size cannot be zero!");
+        }
+        if size > self.len() {
+            return;
+        }
+        for start in 0..=self.len() - size {
+            f(&mut self.slice_mut(start..start + size));
+        }
+    }
+
+    /// Merge the adjacent sorted ranges `start..mid` and `mid..end` into a
+    /// single sorted range.
+    ///
+    /// The merge is stable, and allocates a temporary buffer the size of the
+    /// first range. See `inplace_merge_unbuffered` for a version which
+    /// doesn't allocate.
+    ///
+    /// Panics unless `start <= mid <= end <= self.len()`.
+    fn inplace_merge(&mut self, start: usize, mid: usize, end: usize)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.inplace_merge_by(start, mid, end, Ord::cmp)
+    }
+
+    /// Merge the adjacent sorted ranges `start..mid` and `mid..end` into a
+    /// single sorted range using a comparator function.
+    ///
+    /// The merge is stable, and allocates a temporary buffer the size of the
+    /// first range.
+    ///
+    /// Panics unless `start <= mid <= end <= self.len()`.
+    fn inplace_merge_by<F>(&mut self, start: usize, mid: usize, end: usize, mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        check_merge_bounds(start, mid, end, self.len());
+        merge_buffered(self, start, mid, end, &mut compare)
+    }
+
+    /// Merge the adjacent sorted ranges `start..mid` and `mid..end` into a
+    /// single sorted range without allocating.
+    ///
+    /// The merge is stable, and works by rotating subranges into place,
+    /// which takes O(n log n) swaps rather than the O(n) moves of
+    /// `inplace_merge`.
+    ///
+    /// Panics unless `start <= mid <= end <= self.len()`.
+    fn inplace_merge_unbuffered(&mut self, start: usize, mid: usize, end: usize)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.inplace_merge_unbuffered_by(start, mid, end, Ord::cmp)
+    }
+
+    /// Merge the adjacent sorted ranges `start..mid` and `mid..end` into a
+    /// single sorted range using a comparator function, without allocating.
+    ///
+    /// Panics unless `start <= mid <= end <= self.len()`.
+    fn inplace_merge_unbuffered_by<F>(
+        &mut self,
+        start: usize,
+        mid: usize,
+        end: usize,
+        mut compare: F,
+    ) where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        check_merge_bounds(start, mid, end, self.len());
+        merge_unbuffered(self, start, mid, end, &mut compare)
+    }
+
+    /// Rearrange the array so that the element at `index` is the element
+    /// which would be there if the array were sorted, with every element
+    /// before it less than or equal to it, and every element after it greater
+    /// than or equal to it.
+    ///
+    /// Returns mutable views of the elements before `index` and after it, in
+    /// no particular order, along with a mutable reference to the element at
+    /// `index`. This takes O(n) time on average, so it's much cheaper than
+    /// sorting the whole array to find a median or percentile.
+    ///
+    /// Panics if `index` is out of bounds.
+    fn select_nth_unstable(&mut self, index: usize) -> NthSplit<'_, Self>
+    where
+        Self: DisjointIndexMut,
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.select_nth_unstable_by(index, Ord::cmp)
+    }
+
+    /// Rearrange the array using a comparator function so that the element
+    /// at `index` is the element which would be there if the array were
+    /// sorted.
+    ///
+    /// See `select_nth_unstable`.
+    ///
+    /// Panics if `index` is out of bounds.
+    fn select_nth_unstable_by<F>(&mut self, index: usize, mut compare: F) -> NthSplit<'_, Self>
+    where
+        Self: DisjointIndexMut,
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let len = self.len();
+        if index >= len {
+            panic!(
+                "ArrayMut::select_nth_unstable: index {} out of bounds for length {}",
+                index, len
+            );
+        }
+        crate::sort::multiselect(
+            self,
+            0,
+            len - 1,
+            &[index],
+            &mut compare,
+            &mut RandomPivot::default(),
+        );
+        let array = NonNull::from(self);
+        // The three parts cover disjoint ranges of the array, which is
+        // mutably borrowed for as long as they live, and `DisjointIndexMut`
+        // lets them index it at once.
+        unsafe {
+            let element = &mut *Self::get_unchecked_ptr_mut(array.as_ptr(), index);
+            (
+                ArraySliceMut::from_raw(array, 0..index),
+                element,
+                ArraySliceMut::from_raw(array, index + 1..len),
+            )
+        }
+    }
+
+    /// Rearrange the array using a key extractor function so that the
+    /// element at `index` is the element which would be there if the array
+    /// were sorted by key.
+    ///
+    /// See `select_nth_unstable`.
+    ///
+    /// Panics if `index` is out of bounds.
+    fn select_nth_unstable_by_key<F, K>(
+        &mut self,
+        index: usize,
+        mut extract: F,
+    ) -> NthSplit<'_, Self>
+    where
+        Self: DisjointIndexMut,
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.select_nth_unstable_by(index, |l, r| extract(l).cmp(&extract(r)))
+    }
+
+    /// Rearrange the array so that its first `k` elements are its `k`
+    /// smallest elements, in sorted order, leaving the rest of the elements
+    /// in an unspecified order.
+    ///
+    /// This takes O(n + k log k) time on average, so it's much cheaper than
+    /// sorting the whole array when only the first few elements are needed.
+    /// If `k` is at least the length of the array, the whole array is sorted.
+    fn partial_sort(&mut self, k: usize)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.partial_sort_by(k, Ord::cmp)
+    }
+
+    /// Rearrange the array using a comparator function so that its first
+    /// `k` elements are its `k` smallest elements, in sorted order.
+    ///
+    /// See `partial_sort`.
+    fn partial_sort_by<F>(&mut self, k: usize, mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let len = self.len();
+        if k == 0 || len < 2 {
+            return;
+        }
+        if k < len {
+            crate::sort::multiselect(
+                self,
+                0,
+                len - 1,
+                &[k - 1],
+                &mut compare,
+                &mut RandomPivot::default(),
+            );
+        }
+        crate::sort::quicksort(self, 0, k.min(len) - 1, |a, b| compare(a, b));
+    }
+
+    /// Rearrange the array so that the element at each of the indexes in
+    /// `ranks` is the element which would be there if the array were sorted,
+    /// with every element before it less than or equal to it, and every
+    /// element after it greater than or equal to it.
+    ///
+    /// This is much cheaper than calling a single element selection method
+    /// once for each rank, because the partitioning work is shared. It's
+    /// useful for finding several percentiles at once.
+    ///
+    /// Panics if any rank is out of bounds.
+    fn select_many_unstable(&mut self, ranks: &[usize])
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.select_many_unstable_by(ranks, Ord::cmp)
+    }
+
+    /// Rearrange the array using a comparator function so that the element
+    /// at each of the indexes in `ranks` is the element which would be there
+    /// if the array were sorted.
+    ///
+    /// Panics if any rank is out of bounds.
+    fn select_many_unstable_by<F>(&mut self, ranks: &[usize], mut compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        let mut ranks = ranks.to_vec();
+        ranks.sort_unstable();
+        ranks.dedup();
+        if let Some(&last) = ranks.last() {
+            if last >= self.len() {
+                panic!(
+                    "ArrayMut::select_many_unstable: rank {} out of bounds for length {}",
+                    last,
+                    self.len()
+                );
+            }
+            crate::sort::multiselect(
+                self,
+                0,
+                self.len() - 1,
+                &ranks,
+                &mut compare,
+                &mut RandomPivot::default(),
+            );
+        }
+    }
+
+    /// Move all but the first of consecutive repeated elements to the end of
+    /// the array, and return the index where they start.
+    ///
+    /// The elements before the returned index are in their original order,
+    /// with no consecutive repeats. The order of the elements after it is
+    /// unspecified.
+    fn partition_dedup(&mut self) -> usize
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        self.partition_dedup_by(|a, b| a == b)
+    }
+
+    /// Move all but the first of consecutive elements which `same_bucket`
+    /// considers equal to the end of the array, and return the index where
+    /// they start.
+    ///
+    /// `same_bucket` is called with each element and the last element kept
+    /// before it, in that order.
+    fn partition_dedup_by<F>(&mut self, mut same_bucket: F) -> usize
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> bool,
+    {
+        if self.is_empty() {
+            return 0;
+        }
+        let mut write = 1;
+        for read in 1..self.len() {
+            if !same_bucket(&self[read], &self[write - 1]) {
+                self.swap(read, write);
+                write += 1;
+            }
+        }
+        write
+    }
+
+    /// Move all but the first of consecutive elements with equal keys to the
+    /// end of the array, and return the index where they start.
+    fn partition_dedup_by_key<F, K>(&mut self, mut extract: F) -> usize
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: PartialEq,
+    {
+        self.partition_dedup_by(|a, b| extract(a) == extract(b))
+    }
+
+    /// Call a function on a mutable view of each consecutive chunk of `size`
+    /// elements of the array, in parallel on the rayon thread pool.
+    ///
+    /// The last chunk will be shorter than `size` if the array's length isn't
+    /// divisible by it.
+    ///
+    /// Panics if `size` is zero.
+    #[cfg(feature = "rayon")]
+    fn par_for_each_chunk_mut<F>(&mut self, size: usize, f: F)
+    where
+        Self: DisjointIndexMut + Send + Sync,
+        F: Fn(&mut ArraySliceMut<'_, Self>) + Sync,
+    {
+        if size == 0 {
+            panic!("ArrayMut::par_for_each_chunk_mut: chunk size cannot be zero!");
+        }
+        /// Split `view` in half on a chunk boundary until only one chunk
+        /// is left, letting rayon run the halves in parallel.
+        fn for_each_chunk<Arr, F>(mut view: ArraySliceMut<'_, Arr>, size: usize, f: &F)
+        where
+            Arr: DisjointIndexMut + Send + Sync + ?Sized,
+            F: Fn(&mut ArraySliceMut<'_, Arr>) + Sync,
+        {
+            let len = view.len();
+            if len <= size {
+                return f(&mut view);
+            }
+            let chunks = len.div_ceil(size);
+            let (left, right) = view.split_at(chunks / 2 * size);
+            rayon::join(
+                || for_each_chunk(left, size, f),
+                || for_each_chunk(right, size, f),
+            );
+        }
+
+        if size == 0 {
+            panic!("ArrayMut::par_for_each_chunk_mut: chunk size cannot be zero!");
+        }
+        if self.is_empty() {
+            return;
+        }
+        for_each_chunk(self.slice_mut(..), size, &f);
+    }
+}
+
+/// Trait for arrays which can hand out mutable references to several of
+/// their elements at once.
+///
+/// Methods which produce more than one live mutable reference into an array,
+/// such as `ArrayMut::split_n_mut`, are only available for arrays which
+/// implement this trait.
+///
+/// # Safety
+///
+/// Those methods share a raw pointer to the array, and reach each element
+/// they hand out through `ArrayMut::get_unchecked_ptr_mut`, while references
+/// to other elements are still alive. They may also call `index` and
+/// `get_unchecked` through a shared reference to the array. By implementing
+/// this trait, you promise that this is sound for your type, which means that
+/// for indexes less than the array's length:
+///
+/// * you override `get_unchecked_ptr_mut` so that it never creates a
+///   reference to the array, only reading through the pointer it's given;
+/// * it returns pointers to distinct, non-overlapping elements for distinct
+///   indexes;
+/// * neither it nor `index` and `get_unchecked` write to the array value
+///   itself, move, reallocate or drop any element, or create a reference to
+///   any element other than the one asked for, so references returned
+///   earlier for other indexes stay valid;
+/// * if the array is `Sync`, they can be called from several threads at once
+///   for distinct indexes.
+///
+/// In practice, this means the elements live in storage outside the array
+/// value itself, such as on the heap, and the array is only read to find
+/// them. Arrays which store their elements inline, copy-on-write arrays,
+/// arrays which reach their elements through a slice of the whole storage,
+/// like `Vec`, and arrays where several indexes refer to the same element
+/// must not implement it.
+pub unsafe trait DisjointIndexMut: ArrayMut {}
+
+/// Trait for arrays which can grow and shrink.
+pub trait ArrayResize: ArrayMut {
+    /// Insert an element at the given index, shifting every element after it
+    /// one position up.
+    ///
+    /// Panics if `index > self.len()`.
+    fn insert(&mut self, index: usize, value: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Sized;
+
+    /// Remove the element at the given index, shifting every element after it
+    /// one position down.
+    ///
+    /// Returns the removed element, or `None` if the index is out of bounds.
+    fn remove(&mut self, index: usize) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Sized;
+
+    /// Add an element to the end of the array.
+    fn push(&mut self, value: <Self as Index<usize>>::Output)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.insert(self.len(), value)
+    }
+
+    /// Remove the last element of the array and return it.
+    fn pop(&mut self) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        if self.is_empty() {
+            None
+        } else {
+            self.remove(self.len() - 1)
+        }
+    }
+
+    /// Shorten the array to `len` elements, dropping the rest.
+    ///
+    /// Does nothing if the array is already shorter than `len`.
+    fn truncate(&mut self, len: usize)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        while self.len() > len {
+            self.pop();
+        }
+    }
+
+    /// Remove every element from the array.
+    fn clear(&mut self)
+    where
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.truncate(0)
+    }
+
+    /// Remove consecutive repeated elements, keeping the first of each run,
+    /// and return the length of each run.
+    ///
+    /// The returned vector has one entry for each element left in the
+    /// array.
+    fn dedup_with_count(&mut self) -> Vec<usize>
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        let mut counts = Vec::new();
+        if self.is_empty() {
+            return counts;
+        }
+        counts.push(1);
+        let mut write = 0;
+        for read in 1..self.len() {
+            if self[read] == self[write] {
+                counts[write] += 1;
+            } else {
+                write += 1;
+                self.swap(write, read);
+                counts.push(1);
+            }
+        }
+        self.truncate(write + 1);
+        counts
+    }
+}
+
+/// Swap the element at `left_index` in `left` with the element at
+/// `right_index` in `right`.
+///
+/// Panics if either index is out of bounds.
+pub fn swap_between<L, R>(left: &mut L, left_index: usize, right: &mut R, right_index: usize)
+where
+    L: ArrayMut + ?Sized,
+    R: ArrayMut + IndexMut<usize, Output = <L as Index<usize>>::Output> + ?Sized,
+    <L as Index<usize>>::Output: Sized,
+{
+    match (left.get_mut(left_index), right.get_mut(right_index)) {
+        (Some(left), Some(right)) => std::mem::swap(left, right),
+        _ => panic!(
+            "swap_between: index out of bounds: the lengths are {} and {} but the indices are {} and {}",
+            left.len(),
+            right.len(),
+            left_index,
+            right_index
+        ),
+    }
+}
+
+/// Holds elements moved out of an array by `ArrayMut::swap_unchecked` or
+/// `ArrayMut::map_pair`, and moves them back to their indexes when filled or
+/// dropped.
+///
+/// Each pointer is taken from `get_unchecked_mut` right before writing
+/// through it, and a value only leaves the guard once its pointer is in hand,
+/// so if `index_mut` panics, the drop tries again instead of losing the
+/// element.
+struct PairGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    array: &'a mut Arr,
+    index1: usize,
+    index2: usize,
+    value1: Option<<Arr as Index<usize>>::Output>,
+    value2: Option<<Arr as Index<usize>>::Output>,
+}
+
+impl<'a, Arr> PairGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    fn fill(&mut self) {
+        // The indexes were in bounds when the elements were moved out.
+        unsafe {
+            if self.value1.is_some() {
+                let hole: *mut _ = self.array.get_unchecked_mut(self.index1);
+                if let Some(value) = self.value1.take() {
+                    std::ptr::write(hole, value);
+                }
+            }
+            if self.value2.is_some() {
+                let hole: *mut _ = self.array.get_unchecked_mut(self.index2);
+                if let Some(value) = self.value2.take() {
+                    std::ptr::write(hole, value);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, Arr> Drop for PairGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    fn drop(&mut self) {
+        self.fill();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+    use std::iter::FromIterator;
+    use std::rc::Rc;
+
+    #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    struct TestVec<A>(Vec<A>);
+
+    impl<A> HasLength for TestVec<A> {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl<A> Index<usize> for TestVec<A> {
+        type Output = A;
+        fn index(&self, index: usize) -> &A {
+            &self.0[index]
+        }
+    }
+
+    impl<A> IndexMut<usize> for TestVec<A> {
+        fn index_mut(&mut self, index: usize) -> &mut A {
+            &mut self.0[index]
+        }
+    }
+
+    impl<A> Array for TestVec<A> {}
+    impl<A> ArrayMut for TestVec<A> {}
+
+    impl<A> ArrayResize for TestVec<A> {
+        fn insert(&mut self, index: usize, value: A) {
+            self.0.insert(index, value)
+        }
+
+        fn remove(&mut self, index: usize) -> Option<A> {
+            if index < self.0.len() {
+                Some(self.0.remove(index))
+            } else {
+                None
+            }
+        }
+    }
+
+    impl<A> FromIterator<A> for TestVec<A> {
+        fn from_iter<I>(iter: I) -> Self
+        where
+            I: IntoIterator<Item = A>,
+        {
+            Self(Vec::from_iter(iter))
+        }
+    }
+
+    impl<A> From<Vec<A>> for TestVec<A> {
+        fn from(vec: Vec<A>) -> Self {
+            Self(vec)
+        }
+    }
+
+    /// A copy-on-write array, which unshares its storage in `index_mut`.
+    ///
+    /// The second field counts down the calls to `index_mut`, which panics
+    /// once when it reaches zero.
+    #[derive(Clone)]
+    struct CowVec<A>(Rc<Vec<A>>, usize);
+
+    impl<A> HasLength for CowVec<A> {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl<A> Index<usize> for CowVec<A> {
+        type Output = A;
+        fn index(&self, index: usize) -> &A {
+            &self.0[index]
+        }
+    }
+
+    impl<A: Clone> IndexMut<usize> for CowVec<A> {
+        fn index_mut(&mut self, index: usize) -> &mut A {
+            self.1 = self.1.wrapping_sub(1);
+            if self.1 == usize::MAX {
+                panic!("CowVec::index_mut: out of calls");
+            }
+            &mut Rc::make_mut(&mut self.0)[index]
+        }
+    }
+
+    impl<A> Array for CowVec<A> {}
+    impl<A: Clone> ArrayMut for CowVec<A> {}
+
+    /// Keeps track of which `Counted` elements are alive.
+    #[derive(Debug, Default)]
+    struct Tracker {
+        next_id: usize,
+        live: HashSet<usize>,
+        dropped_twice: usize,
+    }
+
+    /// An element which records its creation and destruction in a `Tracker`.
+    #[derive(Debug)]
+    struct Counted(u32, usize, Rc<RefCell<Tracker>>);
+
+    impl Counted {
+        fn new(value: u32, tracker: &Rc<RefCell<Tracker>>) -> Self {
+            let id = {
+                let mut tracker = tracker.borrow_mut();
+                tracker.next_id += 1;
+                let id = tracker.next_id;
+                tracker.live.insert(id);
+                id
+            };
+            Counted(value, id, tracker.clone())
+        }
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            Counted::new(self.0, &self.2)
+        }
+    }
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            let mut tracker = self.2.borrow_mut();
+            if !tracker.live.remove(&self.1) {
+                tracker.dropped_twice += 1;
+            }
+        }
+    }
+
+    impl PartialEq for Counted {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Counted {}
+
+    impl PartialOrd for Counted {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Counted {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.0.cmp(&other.0)
+        }
+    }
+
+    /// Call `f` on a copy-on-write array of the numbers `0..100` in a
+    /// scrambled order. See `check_copy_on_write_with`.
+    fn check_copy_on_write<F>(calls: usize, f: F) -> Vec<u32>
+    where
+        F: FnOnce(&mut CowVec<Counted>),
+    {
+        let values: Vec<_> = (0..100).map(|n| n * 37 % 100).collect();
+        check_copy_on_write_with(&values, calls, f)
+    }
+
+    /// Call `f` on a copy-on-write array of `values` whose storage is shared
+    /// with another and which allows `calls` calls to `index_mut`, and check
+    /// that no element is dropped twice or leaked, even if `f` panics, and
+    /// that the other array is left untouched.
+    fn check_copy_on_write_with<F>(values: &[u32], calls: usize, f: F) -> Vec<u32>
+    where
+        F: FnOnce(&mut CowVec<Counted>),
+    {
+        let tracker = Rc::new(RefCell::new(Tracker::default()));
+        let result = {
+            let values: Vec<_> = values
+                .iter()
+                .map(|&value| Counted::new(value, &tracker))
+                .collect();
+            let original: Vec<_> = values.iter().map(|value| value.0).collect();
+            let mut vec = CowVec(Rc::new(values), calls);
+            let shared = vec.clone();
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut vec)));
+            assert_eq!(
+                original,
+                shared.0.iter().map(|value| value.0).collect::<Vec<_>>()
+            );
+            let result = vec.0.iter().map(|value| value.0).collect();
+            drop(shared);
+            assert_eq!(original.len(), RefCell::borrow(&tracker).live.len());
+            result
+        };
+        assert_eq!(0, RefCell::borrow(&tracker).dropped_twice);
+        assert!(RefCell::borrow(&tracker).live.is_empty());
+        result
+    }
+
+    #[test]
+    fn ops() {
+        let mut vec = TestVec::from_iter(1..=3);
+        assert_eq!(3, vec.len());
+        assert_eq!(Some(&1), vec.first());
+        assert_eq!(Some(&2), vec.get(1));
+        assert_eq!(Some(&3), vec.last());
+        *vec.first_mut().unwrap() = 3;
+        *vec.last_mut().unwrap() = 1;
+        *vec.get_mut(1).unwrap() = 5;
+        vec.swap(0, 1);
+        assert_eq!(TestVec::from(vec![5, 3, 1]), vec);
+        assert!(!vec.is_sorted());
+        vec.sort_unstable();
+        assert_eq!(TestVec::from(vec![1, 3, 5]), vec);
+        assert!(vec.is_sorted());
+        assert_eq!(Ok(1), vec.binary_search(&3));
+        assert_eq!(Err(1), vec.binary_search(&2));
+        assert_eq!(Err(0), vec.binary_search(&0));
+        assert_eq!(Err(3), vec.binary_search(&1337));
+        assert!(vec.contains(&1));
+        assert!(!vec.contains(&2));
+        assert!(vec.contains(&3));
+        assert!(!vec.contains(&4));
+        assert!(vec.contains(&5));
+        assert!(vec.starts_with(&[1, 3]));
+        assert!(!vec.starts_with(&[1, 2, 3]));
+        assert!(vec.ends_with(&[3, 5]));
+        assert!(!vec.ends_with(&[3, 4, 5]));
+    }
+
+    #[test]
+    fn adjacent_difference() {
+        let mut vec = TestVec::from(vec![1, 3, 6, 10]);
+        vec.adjacent_difference();
+        assert_eq!(TestVec::from(vec![1, 2, 3, 4]), vec);
+        let mut vec = TestVec::from(vec![5, 7, 8]);
+        vec.adjacent_difference_from(2);
+        assert_eq!(TestVec::from(vec![3, 2, 1]), vec);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        empty.adjacent_difference_from(1);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn fill() {
+        let mut vec = TestVec::from(vec![0; 4]);
+        vec.fill(7);
+        assert_eq!(TestVec::from(vec![7; 4]), vec);
+        let mut counter = 0;
+        vec.fill_with(|| {
+            counter += 1;
+            counter
+        });
+        assert_eq!(TestVec::from(vec![1, 2, 3, 4]), vec);
+    }
+
+    #[test]
+    fn from_slice() {
+        let mut vec = TestVec::from(vec![0; 3]);
+        vec.copy_from_slice(&[1, 2, 3]);
+        assert_eq!(TestVec::from(vec![1, 2, 3]), vec);
+        let mut vec = TestVec::from(vec![String::new(); 2]);
+        vec.clone_from_slice(&["a".to_string(), "b".to_string()]);
+        assert_eq!(TestVec::from(vec!["a".to_string(), "b".to_string()]), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn copy_from_slice_length_mismatch() {
+        let mut vec = TestVec::from(vec![0; 3]);
+        vec.copy_from_slice(&[1, 2]);
+    }
+
+    #[test]
+    fn copy_within() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3, 4, 5]);
+        vec.copy_within(1..4, 2);
+        assert_eq!(TestVec::from(vec![0, 1, 1, 2, 3, 5]), vec);
+        vec.copy_within(2.., 0);
+        assert_eq!(TestVec::from(vec![1, 2, 3, 5, 3, 5]), vec);
+        vec.copy_within(..0, 6);
+        assert_eq!(TestVec::from(vec![1, 2, 3, 5, 3, 5]), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn copy_within_out_of_bounds() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3]);
+        vec.copy_within(..2, 3);
+    }
+
+    #[test]
+    fn swap_with() {
+        let mut vec = TestVec::from(vec![0, 1, 2]);
+        let mut slice = [3, 4, 5];
+        vec.swap_with_slice(&mut slice);
+        assert_eq!(TestVec::from(vec![3, 4, 5]), vec);
+        assert_eq!([0, 1, 2], slice);
+        let mut other = TestVec::from(vec![6, 7, 8]);
+        vec.swap_with_array(&mut other);
+        assert_eq!(TestVec::from(vec![6, 7, 8]), vec);
+        assert_eq!(TestVec::from(vec![3, 4, 5]), other);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_with_array_length_mismatch() {
+        let mut vec = TestVec::from(vec![0, 1, 2]);
+        vec.swap_with_array(&mut TestVec::from(vec![0, 1]));
+    }
+
+    #[test]
+    fn fill_with_index() {
+        let mut vec = TestVec::from(vec![0; 4]);
+        vec.fill_with_index(|i| i * 2);
+        assert_eq!(TestVec::from(vec![0, 2, 4, 6]), vec);
+    }
+
+    #[test]
+    fn range_mutations() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3, 4, 5]);
+        vec.reverse_range(1..4);
+        assert_eq!(TestVec::from(vec![0, 3, 2, 1, 4, 5]), vec);
+        vec.reverse_range(..);
+        assert_eq!(TestVec::from(vec![5, 4, 1, 2, 3, 0]), vec);
+        vec.rotate_range(2.., 1);
+        assert_eq!(TestVec::from(vec![5, 4, 2, 3, 0, 1]), vec);
+        vec.rotate_range(..=2, 3);
+        assert_eq!(TestVec::from(vec![5, 4, 2, 3, 0, 1]), vec);
+        vec.rotate_range(..2, 0);
+        assert_eq!(TestVec::from(vec![5, 4, 2, 3, 0, 1]), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rotate_range_count_out_of_bounds() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3]);
+        vec.rotate_range(1..3, 3);
     }
 
     #[test]
-    fn ops() {
-        let mut vec = TestVec::from_iter(1..=3);
-        assert_eq!(3, vec.len());
-        assert_eq!(Some(&1), vec.first());
-        assert_eq!(Some(&2), vec.get(1));
-        assert_eq!(Some(&3), vec.last());
-        *vec.first_mut().unwrap() = 3;
-        *vec.last_mut().unwrap() = 1;
-        *vec.get_mut(1).unwrap() = 5;
-        vec.swap(0, 1);
-        assert_eq!(TestVec::from(vec![5, 3, 1]), vec);
-        assert!(!vec.is_sorted());
-        vec.sort_unstable();
-        assert_eq!(TestVec::from(vec![1, 3, 5]), vec);
-        assert!(vec.is_sorted());
-        assert_eq!(Ok(1), vec.binary_search(&3));
-        assert_eq!(Err(1), vec.binary_search(&2));
-        assert_eq!(Err(0), vec.binary_search(&0));
-        assert_eq!(Err(3), vec.binary_search(&1337));
-        assert!(vec.contains(&1));
-        assert!(!vec.contains(&2));
-        assert!(vec.contains(&3));
-        assert!(!vec.contains(&4));
-        assert!(vec.contains(&5));
-        assert!(vec.starts_with(&[1, 3]));
-        assert!(!vec.starts_with(&[1, 2, 3]));
-        assert!(vec.ends_with(&[3, 5]));
-        assert!(!vec.ends_with(&[3, 4, 5]));
+    fn compact() {
+        let mut vec = TestVec::from(vec![None, Some(1), None, None, Some(2), Some(3), None]);
+        assert_eq!(3, vec.compact());
+        assert_eq!(
+            TestVec::from(vec![Some(1), Some(2), Some(3), None, None, None, None]),
+            vec
+        );
+        let mut empty: TestVec<Option<u8>> = TestVec::from(vec![]);
+        assert_eq!(0, empty.compact());
+    }
+
+    #[test]
+    fn move_element() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3, 4]);
+        vec.move_element(1, 3);
+        assert_eq!(TestVec::from(vec![0, 2, 3, 1, 4]), vec);
+        vec.move_element(4, 0);
+        assert_eq!(TestVec::from(vec![4, 0, 2, 3, 1]), vec);
+        vec.move_element(2, 2);
+        assert_eq!(TestVec::from(vec![4, 0, 2, 3, 1]), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn move_element_out_of_bounds() {
+        let mut vec = TestVec::from(vec![0, 1, 2]);
+        vec.move_element(0, 3);
+    }
+
+    #[test]
+    fn shift() {
+        let mut vec = TestVec::from(vec![1, 2, 3, 4, 5]);
+        vec.shift_left(2, 0);
+        assert_eq!(TestVec::from(vec![3, 4, 5, 0, 0]), vec);
+        vec.shift_right(1, 9);
+        assert_eq!(TestVec::from(vec![9, 3, 4, 5, 0]), vec);
+        vec.shift_right_with(0, || unreachable!());
+        assert_eq!(TestVec::from(vec![9, 3, 4, 5, 0]), vec);
+        vec.shift_left_with(7, Default::default);
+        assert_eq!(TestVec::from(vec![0; 5]), vec);
+        let mut strings = TestVec::from(vec!["a".to_string(), "b".to_string()]);
+        strings.shift_right_with(1, String::new);
+        assert_eq!(TestVec::from(vec![String::new(), "a".to_string()]), strings);
+    }
+
+    #[test]
+    fn clamp_all() {
+        let mut vec = TestVec::from(vec![-5, 0, 3, 7, 12]);
+        vec.clamp_all(&0, &10);
+        assert_eq!(TestVec::from(vec![0, 0, 3, 7, 10]), vec);
+        let mut vec = TestVec::from(vec![-1.5, f64::NAN, 2.5]);
+        vec.clamp_all(&-1.0, &1.0);
+        assert_eq!(-1.0, vec[0]);
+        assert!(vec[1].is_nan());
+        assert_eq!(1.0, vec[2]);
+    }
+
+    #[test]
+    fn zip_apply_and_dot() {
+        let mut vec = TestVec::from(vec![1, 2, 3]);
+        let other = TestVec::from(vec![4, 5, 6]);
+        assert_eq!(32, vec.dot(&other));
+        vec.zip_apply(&other, |a, b| *a += b);
+        assert_eq!(TestVec::from(vec![5, 7, 9]), vec);
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(0, empty.dot(&empty));
+    }
+
+    #[test]
+    fn resize() {
+        let mut vec = TestVec::from(vec![1, 2, 3]);
+        vec.push(4);
+        vec.insert(0, 0);
+        assert_eq!(TestVec::from(vec![0, 1, 2, 3, 4]), vec);
+        assert_eq!(Some(4), vec.pop());
+        assert_eq!(Some(1), vec.remove(1));
+        assert_eq!(None, vec.remove(3));
+        vec.truncate(1);
+        assert_eq!(TestVec::from(vec![0]), vec);
+        vec.clear();
+        assert_eq!(None, vec.pop());
+    }
+
+    #[test]
+    fn for_each_window_mut() {
+        let mut vec = TestVec::from(vec![1, 2, 3, 4, 5]);
+        let mut count = 0;
+        vec.for_each_window_mut(3, |window| {
+            assert_eq!(3, window.len());
+            window[2] += window[0];
+            count += 1;
+        });
+        assert_eq!(3, count);
+        assert_eq!(TestVec::from(vec![1, 2, 4, 6, 9]), vec);
+        vec.for_each_window_mut(6, |_| panic!("window larger than array"));
+    }
+
+    #[test]
+    fn get_many_mut() {
+        let mut vec: VecDeque<_> = (0..4).collect();
+        if let Some([a, b, c]) = vec.get_many_mut([3, 0, 2]) {
+            std::mem::swap(a, b);
+            *c += 10;
+        }
+        assert_eq!(VecDeque::from(vec![3, 1, 12, 0]), vec);
+        assert!(vec.get_many_mut([0, 4]).is_none());
+        assert!(vec.get_many_mut([1, 2, 1]).is_none());
+        assert_eq!(Some([]), vec.get_many_mut::<0>([]));
+    }
+
+    #[test]
+    fn map_pair() {
+        let mut vec = TestVec::from(vec![String::from("a"), String::from("b")]);
+        assert!(vec.get2_mut(0, 1).is_none());
+        let result = vec.map_pair(1, 0, |l, r| {
+            l.push('c');
+            std::mem::swap(l, r);
+            l.len() + r.len()
+        });
+        assert_eq!(3, result);
+        assert_eq!(
+            TestVec::from(vec![String::from("bc"), String::from("a")]),
+            vec
+        );
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            vec.map_pair(0, 1, |l, _| {
+                l.push('d');
+                panic!("map_pair panic")
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(
+            TestVec::from(vec![String::from("bcd"), String::from("a")]),
+            vec
+        );
+    }
+
+    #[test]
+    fn sort_unstable_with() {
+        let mut vec = TestVec::from(vec![5, 1, 4, 2, 3]);
+        vec.sort_unstable_with(crate::sort::MedianOfThree, |l, r| r.cmp(l));
+        assert_eq!(TestVec::from(vec![5, 4, 3, 2, 1]), vec);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        empty.sort_unstable();
+        empty.sort_unstable_with(crate::sort::FirstPivot, Ord::cmp);
+    }
+
+    #[test]
+    fn binary_search_small_and_large() {
+        for len in 0..40 {
+            let vec = TestVec::from_iter((0..len).map(|i| i * 2));
+            for target in -1..len * 2 + 1 {
+                assert_eq!(
+                    vec.0.binary_search(&target),
+                    Array::binary_search(&vec, &target)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn take_and_replace() {
+        let mut vec = TestVec::from(vec![String::from("a"), String::from("b")]);
+        assert_eq!(Some(String::from("a")), vec.take(0));
+        assert_eq!(Some(String::from("b")), vec.replace(1, String::from("c")));
+        assert_eq!(None, vec.take(2));
+        assert_eq!(None, vec.replace(2, String::from("d")));
+        assert_eq!(TestVec::from(vec![String::new(), String::from("c")]), vec);
+    }
+
+    #[test]
+    fn update_and_set_with() {
+        let mut vec = TestVec::from(vec![1, 2, 3]);
+        assert_eq!(Some(2), vec.update(1, |value| std::mem::replace(value, 5)));
+        assert_eq!(None, vec.update(3, |value| *value));
+        assert!(vec.set_with(2, |value| value * 10));
+        assert!(!vec.set_with(3, |value| value));
+        assert_eq!(TestVec::from(vec![1, 5, 30]), vec);
+    }
+
+    #[test]
+    fn descending() {
+        let mut vec = TestVec::from(vec![3, 9, 1, 5, 7]);
+        assert!(!vec.is_sorted_desc());
+        vec.sort_unstable_desc();
+        assert_eq!(TestVec::from(vec![9, 7, 5, 3, 1]), vec);
+        assert!(vec.is_sorted_desc());
+        assert_eq!(Ok(1), vec.binary_search_desc(&7));
+        assert_eq!(Err(2), vec.binary_search_desc(&6));
+        assert_eq!(Err(0), vec.binary_search_desc(&10));
+        assert_eq!(Err(5), vec.binary_search_desc(&0));
+    }
+
+    #[test]
+    fn position_fold_mismatch() {
+        let vec = TestVec::from(vec![1, 2, 3, 4]);
+        assert_eq!(Some(2), vec.position(|n| *n > 2));
+        assert_eq!(None, vec.position(|n| *n > 4));
+        assert_eq!(10, vec.fold(0, |acc, n| acc + n));
+        assert_eq!(None, vec.mismatch(&TestVec::from(vec![1, 2, 3, 4])));
+        assert_eq!(Some(1), vec.mismatch(&TestVec::from(vec![1, 3, 3, 4])));
+        assert_eq!(Some(3), vec.mismatch(&TestVec::from(vec![1, 2, 3])));
+        assert_eq!(Some(4), vec.mismatch(&TestVec::from(vec![1, 2, 3, 4, 5])));
+    }
+
+    #[test]
+    fn inplace_merge() {
+        let mut vec = TestVec::from(vec![9, 1, 4, 7, 2, 3, 8, 0]);
+        vec.inplace_merge(1, 4, 7);
+        assert_eq!(TestVec::from(vec![9, 1, 2, 3, 4, 7, 8, 0]), vec);
+        let mut vec = TestVec::from(vec![9, 1, 4, 7, 2, 3, 8, 0]);
+        vec.inplace_merge_unbuffered(1, 4, 7);
+        assert_eq!(TestVec::from(vec![9, 1, 2, 3, 4, 7, 8, 0]), vec);
+    }
+
+    #[test]
+    fn swap_between() {
+        let mut left = TestVec::from(vec![1, 2, 3]);
+        let mut right = TestVec::from(vec![4, 5]);
+        super::swap_between(&mut left, 0, &mut right, 1);
+        assert_eq!(TestVec::from(vec![5, 2, 3]), left);
+        assert_eq!(TestVec::from(vec![4, 1]), right);
+    }
+
+    #[test]
+    #[should_panic]
+    fn swap_between_out_of_bounds() {
+        let mut left = TestVec::from(vec![1, 2, 3]);
+        let mut right = TestVec::from(vec![4, 5]);
+        super::swap_between(&mut left, 0, &mut right, 2);
+    }
+
+    #[test]
+    fn is_subsequence_of() {
+        let stream = TestVec::from(vec!['a', 'x', 'b', 'y', 'c']);
+        assert!(TestVec::from(vec!['a', 'b', 'c']).is_subsequence_of(&stream));
+        assert!(TestVec::from(vec!['x', 'c']).is_subsequence_of(&stream));
+        assert!(!TestVec::from(vec!['b', 'a']).is_subsequence_of(&stream));
+        assert!(!TestVec::from(vec!['c', 'c']).is_subsequence_of(&stream));
+        assert!(TestVec::from(vec![]).is_subsequence_of(&stream));
+        assert!(stream.is_subsequence_of(&stream));
+    }
+
+    #[test]
+    fn compare_by() {
+        let vec = TestVec::from(vec!["Hello", "World", "Foo"]);
+        let eq = |a: &&str, b: &&str| a.eq_ignore_ascii_case(b);
+        assert!(vec.starts_with_by(&["hello", "WORLD"], eq));
+        assert!(!vec.starts_with_by(&["world"], eq));
+        assert!(vec.ends_with_by(&["world", "foo"], eq));
+        assert!(!vec.ends_with_by(&["hello", "world", "foo", "bar"], eq));
+        let floats = TestVec::from(vec![1.0, 2.0, 3.0]);
+        let close = TestVec::from(vec![1.0001, 1.9999, 3.0]);
+        assert!(floats.eq_by(&close, |a: &f64, b: &f64| (a - b).abs() < 0.001));
+        assert!(!floats.eq_by(&close, |a: &f64, b: &f64| (a - b).abs() < 0.00001));
+        assert!(!floats.eq_by(&TestVec::from(vec![1.0]), |a: &f64, b: &f64| a == b));
+    }
+
+    #[test]
+    fn binary_search_rotated() {
+        let sorted: Vec<i32> = (0..20).map(|n| n * 3).collect();
+        for rotation in 0..sorted.len() {
+            let mut rotated = sorted.clone();
+            rotated.rotate_left(rotation);
+            let vec = TestVec::from(rotated.clone());
+            for target in -1..61 {
+                let expected = rotated.iter().position(|n| *n == target);
+                assert_eq!(expected, vec.binary_search_rotated(&target));
+            }
+        }
+        let vec = TestVec::from(vec![1, 1, 2, 1]);
+        assert_eq!(Some(2), vec.binary_search_rotated(&2));
+        let vec = TestVec::from(vec![2, 2, 2, 0, 1, 2]);
+        assert_eq!(Some(3), vec.binary_search_rotated(&0));
+        assert_eq!(Some(4), vec.binary_search_rotated(&1));
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(None, empty.binary_search_rotated(&0));
+    }
+
+    #[test]
+    fn binary_search_many() {
+        let vec = TestVec::from((0..100).map(|n| n * 2).collect::<Vec<_>>());
+        assert_eq!(
+            vec![Ok(40), Err(0), Err(100), Ok(3), Err(4), Ok(40)],
+            vec.binary_search_many(&[80, -1, 500, 6, 7, 80], |n| *n)
+        );
+        assert!(vec.binary_search_many(&[], |n| *n).is_empty());
+    }
+
+    #[test]
+    fn split_first_last() {
+        let vec = TestVec::from(vec![1, 2, 3]);
+        let (first, rest) = vec.split_first().unwrap();
+        assert_eq!(1, *first);
+        assert_eq!(1..3, rest.range());
+        let (last, rest) = vec.split_last().unwrap();
+        assert_eq!(3, *last);
+        assert_eq!(0..2, rest.range());
+        let (first, rest) = rest.split_first().unwrap();
+        assert_eq!(1, *first);
+        assert_eq!(1, rest.len());
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert!(empty.split_first().is_none());
+        assert!(empty.split_last().is_none());
+    }
+
+    #[test]
+    fn split_chunks() {
+        let vec = TestVec::from(vec![1, 2, 3, 4, 5]);
+        let ([a, b], rest) = vec.split_first_chunk::<2>().unwrap();
+        assert_eq!((&1, &2), (a, b));
+        assert_eq!(2..5, rest.range());
+        let ([c, d, e], rest) = vec.split_last_chunk::<3>().unwrap();
+        assert_eq!((&3, &4, &5), (c, d, e));
+        assert_eq!(0..2, rest.range());
+        assert!(vec.split_first_chunk::<6>().is_none());
+        assert!(vec.split_last_chunk::<6>().is_none());
+        let (empty, rest) = vec.split_first_chunk::<0>().unwrap();
+        assert_eq!(0, empty.len());
+        assert_eq!(5, rest.len());
+    }
+
+    #[test]
+    fn try_into_array() {
+        let vec = TestVec::from(vec![1, 2, 3]);
+        assert_eq!(Ok([1, 2, 3]), vec.try_into_array::<3>());
+        assert_eq!(
+            Err(LengthMismatch {
+                expected: 4,
+                actual: 3
+            }),
+            vec.try_into_array::<4>()
+        );
+        let [first, _, _] = vec.try_into_array().unwrap();
+        assert_eq!(1, first);
+    }
+
+    #[test]
+    fn dedup_with_count() {
+        let mut vec = TestVec::from(vec![1, 1, 2, 3, 3, 3, 1]);
+        assert_eq!(vec![2, 1, 3, 1], vec.dedup_with_count());
+        assert_eq!(TestVec::from(vec![1, 2, 3, 1]), vec);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        assert!(empty.dedup_with_count().is_empty());
+    }
+
+    #[test]
+    fn unzip() {
+        let vec = TestVec::from(vec![(1, 'a'), (2, 'b'), (3, 'c')]);
+        let mut left = TestVec::from(vec![0; 3]);
+        let mut right = TestVec::from(vec![' '; 3]);
+        vec.unzip_into(&mut left, &mut right);
+        assert_eq!(TestVec::from(vec![1, 2, 3]), left);
+        assert_eq!(TestVec::from(vec!['a', 'b', 'c']), right);
+        let (left, right): (TestVec<_>, String) = vec.unzip();
+        assert_eq!(TestVec::from(vec![1, 2, 3]), left);
+        assert_eq!("abc", right);
+    }
+
+    #[test]
+    fn interleave() {
+        let left = TestVec::from(vec![1, 3, 5]);
+        let right = TestVec::from(vec![2, 4, 6]);
+        let mut out = TestVec::from(vec![0; 6]);
+        left.interleave_into(&right, &mut out);
+        assert_eq!(TestVec::from(vec![1, 2, 3, 4, 5, 6]), out);
+        let out: Vec<_> = right.interleave(&left);
+        assert_eq!(vec![2, 1, 4, 3, 6, 5], out);
+    }
+
+    #[test]
+    fn group_indices_by_key() {
+        let vec = TestVec::from(vec!["apple", "bean", "avocado", "beet", "cherry"]);
+        let groups = vec.group_indices_by_key(|s| s.chars().next());
+        assert_eq!(3, groups.len());
+        assert_eq!(vec![0, 2], groups[&Some('a')]);
+        assert_eq!(vec![1, 3], groups[&Some('b')]);
+        assert_eq!(vec![4], groups[&Some('c')]);
+    }
+
+    #[test]
+    fn deduped() {
+        let vec = TestVec::from(vec![3, 1, 3, 2, 1, 4, 2]);
+        let unique: TestVec<_> = vec.deduped();
+        assert_eq!(TestVec::from(vec![3, 1, 2, 4]), unique);
+        let empty: Vec<i32> = TestVec::from(vec![]).deduped();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn counts() {
+        let vec = TestVec::from(vec!["a", "b", "a", "c", "a", "b"]);
+        let counts = vec.counts();
+        assert_eq!(3, counts.len());
+        assert_eq!(3, counts["a"]);
+        assert_eq!(2, counts["b"]);
+        assert_eq!(1, counts["c"]);
+        let lengths = TestVec::from(vec!["apple", "fig", "kiwi", "pear"]).counts_by(|s| s.len());
+        assert_eq!(Some(&2), lengths.get(&4));
+        assert_eq!(Some(&1), lengths.get(&5));
+        assert_eq!(None, lengths.get(&2));
+    }
+
+    #[test]
+    fn set_range() {
+        let mut vec = TestVec::from(vec![0; 6]);
+        vec.set_range(1, &[1, 2, 3]);
+        assert_eq!(TestVec::from(vec![0, 1, 2, 3, 0, 0]), vec);
+        vec.set_range_from_iter(4, 8..10);
+        assert_eq!(TestVec::from(vec![0, 1, 2, 3, 8, 9]), vec);
+        vec.set_range(6, &[]);
+        assert_eq!(TestVec::from(vec![0, 1, 2, 3, 8, 9]), vec);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_range_out_of_bounds() {
+        let mut vec = TestVec::from(vec![0; 4]);
+        vec.set_range(2, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn k_smallest_into() {
+        let vec = TestVec::from(vec![8, 3, 9, 1, 7, 3, 5, 2]);
+        let mut out = TestVec::from(vec![0; 3]);
+        assert_eq!(3, vec.k_smallest_into(&mut out));
+        assert_eq!(TestVec::from(vec![1, 2, 3]), out);
+        assert_eq!(3, vec.k_largest_into(&mut out));
+        assert_eq!(TestVec::from(vec![9, 8, 7]), out);
+        let mut out = TestVec::from(vec![0; 10]);
+        assert_eq!(8, vec.k_smallest_into(&mut out));
+        assert_eq!(TestVec::from(vec![1, 2, 3, 3, 5, 7, 8, 9, 0, 0]), out);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(0, vec.k_smallest_into(&mut empty));
+    }
+
+    #[test]
+    fn mode_and_majority() {
+        let vec = TestVec::from(vec![3, 1, 2, 1, 3, 4]);
+        assert_eq!(Some(&3), vec.mode());
+        assert_eq!(None, vec.majority_element());
+        let vec = TestVec::from(vec![2, 5, 2, 2, 7, 2]);
+        assert_eq!(Some(&2), vec.mode());
+        assert_eq!(Some(&2), vec.majority_element());
+        let vec = TestVec::from(vec![1, 2, 1, 2]);
+        assert_eq!(None, vec.majority_element());
+        let empty: TestVec<u8> = TestVec::from(vec![]);
+        assert_eq!(None, empty.mode());
+        assert_eq!(None, empty.majority_element());
+    }
+
+    #[test]
+    fn select_many_unstable() {
+        let mut vec = TestVec::from(vec![9, 3, 7, 1, 5, 8, 2, 6, 4, 0]);
+        vec.select_many_unstable(&[7, 2, 2]);
+        assert_eq!(2, vec[2]);
+        assert_eq!(7, vec[7]);
+        vec.select_many_unstable_by(&[0], |l, r| r.cmp(l));
+        assert_eq!(9, vec[0]);
+    }
+
+    #[test]
+    fn partition_dedup() {
+        let mut vec = TestVec::from(vec![1, 2, 2, 3, 3, 2, 1, 1]);
+        let split = vec.partition_dedup();
+        assert_eq!(5, split);
+        assert_eq!(&[1, 2, 3, 2, 1], &vec.0[..split]);
+        let mut vec = TestVec::from(vec![10, 11, 20, 35, 31]);
+        assert_eq!(3, vec.partition_dedup_by_key(|n| n / 10));
+        assert_eq!(&[10, 20, 35], &vec.0[..3]);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(0, empty.partition_dedup());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_for_each_chunk_mut() {
+        let mut vec: VecDeque<_> = (0..100).collect();
+        vec.par_for_each_chunk_mut(7, |chunk| {
+            let offset = chunk.range().start;
+            chunk.fill_with_index(|index| index + offset);
+            chunk.sort_unstable_desc();
+        });
+        for (start, chunk) in (0..100).step_by(7).zip(vec.make_contiguous().chunks(7)) {
+            assert_eq!(start + chunk.len() - 1, chunk[0]);
+            assert!(chunk.windows(2).all(|pair| pair[0] > pair[1]));
+        }
+    }
+
+    #[test]
+    fn insertion_index() {
+        let vec = TestVec::from(vec![1, 3, 5, 7]);
+        assert_eq!(0, vec.insertion_index(&0));
+        assert_eq!(2, vec.insertion_index(&4));
+        assert_eq!(2, vec.insertion_index(&5));
+        assert_eq!(4, vec.insertion_index(&8));
+        assert_eq!(1, vec.insertion_index_by(|n| n.cmp(&2)));
+        assert_eq!(3, vec.insertion_index_by_key(&12, |n| n * 2));
+    }
+
+    #[test]
+    fn sort_unstable_min_writes() {
+        let mut vec = TestVec::from(vec![5, 3, 3, 9, 1, 7, 3]);
+        vec.sort_unstable_min_writes();
+        assert_eq!(TestVec::from(vec![1, 3, 3, 3, 5, 7, 9]), vec);
+        vec.sort_unstable_min_writes_by(|l, r| r.cmp(l));
+        assert_eq!(TestVec::from(vec![9, 7, 5, 3, 3, 3, 1]), vec);
+    }
+
+    #[test]
+    fn sort_with_spec() {
+        let mut vec = TestVec::from(vec![("b", 2, 0), ("a", 1, 1), ("a", 2, 2), ("b", 2, 3)]);
+        vec.sort_with_spec(
+            SortSpec::by_key(|row: &(&str, i32, i32)| row.1)
+                .desc()
+                .then_by_key(|row| row.0),
+        );
+        assert_eq!(
+            TestVec::from(vec![("a", 2, 2), ("b", 2, 0), ("b", 2, 3), ("a", 1, 1)]),
+            vec
+        );
+    }
+
+    #[test]
+    fn sort_unstable_by_ref_key() {
+        struct Person {
+            name: String,
+            age: u8,
+        }
+        let mut vec = TestVec::from(vec![
+            Person {
+                name: "Cat".to_string(),
+                age: 3,
+            },
+            Person {
+                name: "Ann".to_string(),
+                age: 1,
+            },
+            Person {
+                name: "Bob".to_string(),
+                age: 2,
+            },
+        ]);
+        vec.sort_unstable_by_ref_key(|person| person.name.as_str());
+        assert_eq!(
+            vec![1, 2, 3],
+            vec.0.iter().map(|p| p.age).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn sort_unstable_indirect() {
+        let mut vec = TestVec::from(vec![[5u8; 64], [1; 64], [3; 64], [1; 64]]);
+        vec.sort_unstable_indirect();
+        let firsts: Vec<_> = vec.0.iter().map(|block| block[0]).collect();
+        assert_eq!(vec![1, 1, 3, 5], firsts);
+        vec.sort_unstable_indirect_by(|l, r| r.cmp(l));
+        let firsts: Vec<_> = vec.0.iter().map(|block| block[0]).collect();
+        assert_eq!(vec![5, 3, 1, 1], firsts);
+    }
+
+    #[test]
+    fn is_sorted_by() {
+        let vec = TestVec::from(vec![1, 2, 2, 5]);
+        assert!(vec.is_sorted_by(|l, r| l <= r));
+        assert!(!vec.is_sorted_by(|l, r| l < r));
+        let vec = TestVec::from(vec![1.0, f64::NAN, 2.0]);
+        assert!(!vec.is_sorted_by(|l, r| l <= r));
+        assert!(vec.is_sorted_by_cmp(|l, r| l.partial_cmp(r)));
+        assert!(TestVec::<i32>::from(vec![]).is_sorted_by(|_, _| false));
+    }
+
+    #[test]
+    fn unchecked_access() {
+        let mut vec = TestVec::from(vec![1, 2, 3]);
+        unsafe {
+            assert_eq!(&2, vec.get_unchecked(1));
+            *vec.get_unchecked_mut(0) = 4;
+            vec.swap_unchecked(0, 2);
+        }
+        assert_eq!(TestVec::from(vec![3, 2, 4]), vec);
+    }
+
+    #[test]
+    fn longest_increasing_subsequence() {
+        let vec = TestVec::from(vec![3, 1, 4, 1, 5, 9, 2, 6, 5, 3, 5, 8, 9, 7, 9]);
+        let lis = vec.longest_increasing_subsequence();
+        assert_eq!(6, lis.len());
+        assert!(lis
+            .windows(2)
+            .all(|pair| pair[0] < pair[1] && vec[pair[0]] < vec[pair[1]]));
+        let vec = TestVec::from(vec![5, 4, 3]);
+        assert_eq!(1, vec.longest_increasing_subsequence().len());
+        assert_eq!(
+            vec![0, 1, 2],
+            vec.longest_increasing_subsequence_by(|l, r| r.cmp(l))
+        );
+        let vec = TestVec::from(vec![-1, 3, -2, 5]);
+        assert_eq!(
+            vec![0, 2, 3],
+            vec.longest_increasing_subsequence_by_key(|n: &i32| n.abs())
+        );
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert!(empty.longest_increasing_subsequence().is_empty());
+    }
+
+    #[test]
+    fn sorted_copy_into() {
+        let vec: TestVec<_> = vec![(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd')].into();
+        let mut out: TestVec<_> = vec![(0, ' '); 4].into();
+        vec.sorted_copy_into_by(&mut out, |l, r| l.0.cmp(&r.0));
+        assert_eq!(vec![(1, 'b'), (2, 'd'), (3, 'a'), (3, 'c')], out.0);
+        assert_eq!(vec![(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd')], vec.0);
+        let sorted: Vec<_> = vec.sorted_copy();
+        assert_eq!(vec![(1, 'b'), (2, 'd'), (3, 'a'), (3, 'c')], sorted);
+    }
+
+    #[test]
+    #[should_panic]
+    fn sorted_copy_into_length_mismatch() {
+        let vec: TestVec<_> = vec![3, 1, 2].into();
+        let mut out: TestVec<_> = vec![0; 2].into();
+        vec.sorted_copy_into(&mut out);
+    }
+
+    #[test]
+    fn unsized_elements() {
+        struct Words(Vec<String>);
+
+        impl HasLength for Words {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl Index<usize> for Words {
+            type Output = str;
+            fn index(&self, index: usize) -> &str {
+                &self.0[index]
+            }
+        }
+
+        impl Array for Words {}
+
+        let words = Words(
+            ["ant", "bee", "bee", "cat", "dog"]
+                .iter()
+                .map(|word| word.to_string())
+                .collect(),
+        );
+        assert!(words.contains("cat"));
+        assert_eq!(Ok(3), words.binary_search("cat"));
+        assert_eq!(Err(4), words.binary_search("cow"));
+        assert_eq!(4, words.insertion_index("cow"));
+        assert!(words.is_sorted());
+        assert!(words.starts_with_iter(vec!["ant", "bee"]));
+        assert!(!words.starts_with_iter(vec!["bee"]));
+        assert!(words.ends_with_iter(vec!["cat", "dog"]));
+        assert!(!words.ends_with_iter(vec!["ant", "bee", "bee", "cat", "dog", "eel"]));
+        assert_eq!(Some("bee"), words.mode());
+        assert_eq!(None, words.majority_element());
+        assert_eq!(4, words.iter_deduped().count());
+        assert_eq!(Some((2, "bee")), words.iter_dedup_with_count().nth(1));
+        assert_eq!(Some("bee"), words.window_max(2).next());
+        assert!(words.is_subsequence_of(&words));
+        assert_eq!(None, words.mismatch(&words));
+    }
+
+    #[test]
+    fn binary_search_borrowed() {
+        let vec: TestVec<String> = vec!["ant", "bee", "cat"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(Ok(1), vec.binary_search_borrowed("bee"));
+        assert_eq!(Err(3), vec.binary_search_borrowed("cow"));
+        assert_eq!(Err(0), vec.binary_search_borrowed(""));
+    }
+
+    #[test]
+    fn stable_sort() {
+        let mut vec = TestVec::from(vec![(2, 'a'), (1, 'b'), (2, 'c'), (1, 'd'), (0, 'e')]);
+        vec.sort_by_key(|pair| pair.0);
+        assert_eq!(
+            vec![(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')],
+            vec.0
+        );
+        vec.sort_by(|l, r| r.0.cmp(&l.0));
+        assert_eq!(
+            vec![(2, 'a'), (2, 'c'), (1, 'b'), (1, 'd'), (0, 'e')],
+            vec.0
+        );
+        vec.sort();
+        assert_eq!(
+            vec![(0, 'e'), (1, 'b'), (1, 'd'), (2, 'a'), (2, 'c')],
+            vec.0
+        );
+    }
+
+    #[test]
+    fn sort_unstable_by_cached_key() {
+        let mut vec = TestVec::from(vec![-3i32, 1, -2, 4, 0]);
+        vec.sort_unstable_by_cached_key(|n| n.abs());
+        assert_eq!(vec![0, 1, -2, -3, 4], vec.0);
+    }
+
+    #[test]
+    fn heapsort() {
+        let mut vec = TestVec::from(vec![5, 1, 4, 2, 3, 1]);
+        vec.heapsort();
+        assert_eq!(vec![1, 1, 2, 3, 4, 5], vec.0);
+        vec.heapsort_by(|l, r| r.cmp(l));
+        assert_eq!(vec![5, 4, 3, 2, 1, 1], vec.0);
+        vec.heapsort_by_key(|n: &i32| (n - 3).abs());
+        assert_eq!(3, vec.0[0]);
+        assert!(vec.is_sorted_by_key(|n: &i32| (n - 3).abs()));
+    }
+
+    #[test]
+    fn select_nth_unstable() {
+        let mut vec: VecDeque<_> = vec![9, 3, 7, 1, 5, 8, 2, 6, 4, 0].into();
+        let (before, median, after) = vec.select_nth_unstable(5);
+        assert_eq!(5, *median);
+        assert!((0..before.len()).all(|index| before[index] < 5));
+        assert!((0..after.len()).all(|index| after[index] > 5));
+        assert_eq!((5, 4), (before.len(), after.len()));
+        *median = 50;
+        assert_eq!(50, vec[5]);
+        let (_, largest, after) = vec.select_nth_unstable_by(0, |l, r| r.cmp(l));
+        assert_eq!(50, *largest);
+        assert_eq!(9, after.len());
+        let (before, last, _) = vec.select_nth_unstable_by_key(9, |n: &i32| -n);
+        assert_eq!(0, *last);
+        assert_eq!(9, before.len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_nth_unstable_out_of_bounds() {
+        let mut vec: VecDeque<_> = vec![1, 2, 3].into();
+        vec.select_nth_unstable(3);
+    }
+
+    #[test]
+    fn partial_sort() {
+        let mut vec = TestVec::from(vec![9, 3, 7, 1, 5, 8, 2, 6, 4, 0]);
+        vec.partial_sort(3);
+        assert_eq!(vec![0, 1, 2], vec.0[..3].to_vec());
+        assert!(vec.0[3..].iter().all(|&n| n > 2));
+        vec.partial_sort_by(4, |l, r| r.cmp(l));
+        assert_eq!(vec![9, 8, 7, 6], vec.0[..4].to_vec());
+        vec.partial_sort(20);
+        assert_eq!((0..10).collect::<Vec<_>>(), vec.0);
+        vec.partial_sort_by(0, |l, r| r.cmp(l));
+        assert_eq!((0..10).collect::<Vec<_>>(), vec.0);
+    }
+
+    #[test]
+    fn partition_point() {
+        let vec = TestVec::from(vec![1, 2, 2, 3, 5, 8]);
+        assert_eq!(1, vec.partition_point(|&n| n < 2));
+        assert_eq!(3, vec.partition_point(|&n| n <= 2));
+        assert_eq!(0, vec.partition_point(|&n| n < 0));
+        assert_eq!(6, vec.partition_point(|&n| n < 10));
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(0, empty.partition_point(|_| true));
+    }
+
+    #[test]
+    fn equal_range() {
+        let vec = TestVec::from(vec![1, 2, 2, 2, 3, 5]);
+        assert_eq!(1..4, vec.equal_range(&2));
+        assert_eq!(4..5, vec.equal_range(&3));
+        assert_eq!(5..5, vec.equal_range(&4));
+        assert_eq!(0..0, vec.equal_range(&0));
+        assert_eq!(6..6, vec.equal_range(&9));
+        assert_eq!(1..5, vec.equal_range_by(|n| (n / 2).cmp(&1)));
+        let pairs = TestVec::from(vec![(1, 'a'), (1, 'b'), (2, 'c')]);
+        assert_eq!(0..2, pairs.equal_range_by_key(&1, |pair| pair.0));
+    }
+
+    #[test]
+    fn lower_and_upper_bound() {
+        let vec = TestVec::from(vec![1, 2, 2, 2, 3, 5]);
+        assert_eq!((1, 4), (vec.lower_bound(&2), vec.upper_bound(&2)));
+        assert_eq!((5, 5), (vec.lower_bound(&4), vec.upper_bound(&4)));
+        assert_eq!((0, 0), (vec.lower_bound(&0), vec.upper_bound(&0)));
+        assert_eq!((5, 6), (vec.lower_bound(&5), vec.upper_bound(&5)));
+    }
+
+    #[test]
+    fn exponential_search() {
+        let vec: TestVec<_> = (0..100).map(|n| n * 2).collect();
+        for target in -1..201 {
+            assert_eq!(
+                vec.binary_search(&target),
+                vec.exponential_search(&target),
+                "target {}",
+                target
+            );
+        }
+        assert_eq!(Ok(3), vec.exponential_search_by(|n| n.cmp(&6)));
+        let dups: TestVec<_> = (0..100).map(|n| n / 10).collect();
+        for target in -1..11 {
+            match dups.exponential_search(&target) {
+                Ok(index) => assert_eq!(target, dups[index]),
+                not_found => assert_eq!(dups.binary_search(&target), not_found),
+            }
+        }
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(Err(0), empty.exponential_search(&1));
+    }
+
+    #[test]
+    fn binary_search_with_hint() {
+        let vec: TestVec<_> = (0..50).map(|n| n * 2).collect();
+        for target in -1..101 {
+            for hint in 0..52 {
+                assert_eq!(
+                    vec.binary_search(&target),
+                    vec.binary_search_with_hint(&target, hint),
+                    "target {} hint {}",
+                    target,
+                    hint
+                );
+            }
+        }
+        assert_eq!(Ok(3), vec.binary_search_with_hint_by(40, |n| n.cmp(&6)));
+        let dups: TestVec<_> = (0..50).map(|n| n / 10).collect();
+        for target in -1..6 {
+            for hint in 0..52 {
+                match dups.binary_search_with_hint(&target, hint) {
+                    Ok(index) => assert_eq!(target, dups[index]),
+                    not_found => assert_eq!(dups.binary_search(&target), not_found),
+                }
+            }
+        }
+        let empty: TestVec<i32> = TestVec::from(vec![]);
+        assert_eq!(Err(0), empty.binary_search_with_hint(&1, 5));
+    }
+
+    #[test]
+    fn rotate() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3, 4, 5]);
+        vec.rotate_left(2);
+        assert_eq!(TestVec::from(vec![2, 3, 4, 5, 0, 1]), vec);
+        vec.rotate_right(2);
+        assert_eq!(TestVec::from(vec![0, 1, 2, 3, 4, 5]), vec);
+        vec.rotate_right(1);
+        assert_eq!(TestVec::from(vec![5, 0, 1, 2, 3, 4]), vec);
+        vec.rotate_left(0);
+        vec.rotate_left(6);
+        vec.rotate_right(6);
+        assert_eq!(TestVec::from(vec![5, 0, 1, 2, 3, 4]), vec);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        empty.rotate_left(0);
+        empty.rotate_right(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rotate_left_out_of_bounds() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3]);
+        vec.rotate_left(5);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut vec = TestVec::from(vec![0, 1, 2, 3, 4]);
+        vec.reverse();
+        assert_eq!(TestVec::from(vec![4, 3, 2, 1, 0]), vec);
+        let mut vec = TestVec::from(vec![0, 1, 2, 3]);
+        vec.reverse();
+        assert_eq!(TestVec::from(vec![3, 2, 1, 0]), vec);
+        let mut empty: TestVec<i32> = TestVec::from(vec![]);
+        empty.reverse();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn copy_on_write_swaps() {
+        let result = check_copy_on_write(usize::MAX, |vec| vec.swap(0, 1));
+        assert_eq!(vec![37, 0], result[..2]);
+        let result = check_copy_on_write(usize::MAX, |vec| vec.map_pair(1, 0, std::mem::swap));
+        assert_eq!(vec![37, 0], result[..2]);
+        let result = check_copy_on_write(usize::MAX, |vec| vec.sort_unstable());
+        assert_eq!((0..100).collect::<Vec<_>>(), result);
+    }
+
+    #[test]
+    fn copy_on_write_permutations() {
+        let sorted: Vec<u32> = (0..100).collect();
+        let result = check_copy_on_write(usize::MAX, |vec| vec.sort_unstable_indirect());
+        assert_eq!(sorted, result);
+        let result = check_copy_on_write(usize::MAX, |vec| {
+            vec.sort_unstable_by_cached_key(|value| value.0)
+        });
+        assert_eq!(sorted, result);
+        for calls in (0..200).step_by(7) {
+            let mut result = check_copy_on_write(calls, |vec| vec.sort_unstable_indirect());
+            result.sort_unstable();
+            assert_eq!(sorted, result);
+        }
+    }
+
+    #[test]
+    fn copy_on_write_merges() {
+        // Runs of 16 sorted elements, so the merge sort starts merging
+        // without swapping anything first.
+        let values: Vec<u32> = (0..100).map(|n| n % 16 * 7 + n / 16).collect();
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        let result = check_copy_on_write_with(&values, usize::MAX, |vec| vec.sort());
+        assert_eq!(sorted, result);
+        for calls in (0..400).step_by(13) {
+            let mut result = check_copy_on_write_with(&values, calls, |vec| vec.sort());
+            result.sort_unstable();
+            assert_eq!(sorted, result);
+        }
     }
 }
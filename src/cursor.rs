@@ -0,0 +1,273 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::ops::Index;
+
+use crate::array::{Array, ArrayMut, ArrayResize};
+
+/// A cursor over an array, which can move back and forth between its
+/// elements.
+///
+/// The cursor is positioned at an index from `0` up to and including the
+/// length of the array. The position one past the last element doesn't refer
+/// to an element, and lets a `CursorMut` insert at the end of the array.
+pub struct Cursor<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    index: usize,
+}
+
+impl<'a, Arr> Cursor<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    /// Construct a cursor positioned at the start of `array`.
+    pub fn new(array: &'a Arr) -> Self {
+        Cursor { array, index: 0 }
+    }
+
+    /// Get the index the cursor is positioned at.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Move the cursor to the next element.
+    ///
+    /// Returns `false` without moving if the cursor is already past the last
+    /// element.
+    pub fn move_next(&mut self) -> bool {
+        if self.index < self.array.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move the cursor to the previous element.
+    ///
+    /// Returns `false` without moving if the cursor is already at the start
+    /// of the array.
+    pub fn move_prev(&mut self) -> bool {
+        if self.index > 0 {
+            self.index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move the cursor to `index`.
+    ///
+    /// Panics if `index` is greater than the length of the array.
+    pub fn seek(&mut self, index: usize) {
+        if index > self.array.len() {
+            panic!(
+                "Cursor::seek: index {} out of bounds for length {}",
+                index,
+                self.array.len()
+            );
+        }
+        self.index = index;
+    }
+
+    /// Get a reference to the element the cursor is positioned at, or `None`
+    /// if it's past the last element.
+    pub fn peek(&self) -> Option<&'a <Arr as Index<usize>>::Output> {
+        self.array.get(self.index)
+    }
+
+    /// Get a reference to the element after the cursor.
+    pub fn peek_next(&self) -> Option<&'a <Arr as Index<usize>>::Output> {
+        self.array.get(self.index + 1)
+    }
+
+    /// Get a reference to the element before the cursor.
+    pub fn peek_prev(&self) -> Option<&'a <Arr as Index<usize>>::Output> {
+        self.index
+            .checked_sub(1)
+            .and_then(|index| self.array.get(index))
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for Cursor<'a, Arr> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Arr: ?Sized> Copy for Cursor<'a, Arr> {}
+
+/// A cursor over a mutable array, which can move back and forth between its
+/// elements and modify them.
+///
+/// If the array implements [`ArrayResize`][ArrayResize], the cursor can also
+/// insert and remove elements at its position.
+///
+/// [ArrayResize]: trait.ArrayResize.html
+pub struct CursorMut<'a, Arr: ?Sized> {
+    array: &'a mut Arr,
+    index: usize,
+}
+
+impl<'a, Arr> CursorMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    /// Construct a cursor positioned at the start of `array`.
+    pub fn new(array: &'a mut Arr) -> Self {
+        CursorMut { array, index: 0 }
+    }
+
+    /// Get a read only cursor at the same position as this one.
+    pub fn as_cursor(&self) -> Cursor<'_, Arr> {
+        Cursor {
+            array: self.array,
+            index: self.index,
+        }
+    }
+
+    /// Get the index the cursor is positioned at.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Move the cursor to the next element.
+    ///
+    /// Returns `false` without moving if the cursor is already past the last
+    /// element.
+    pub fn move_next(&mut self) -> bool {
+        if self.index < self.array.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move the cursor to the previous element.
+    ///
+    /// Returns `false` without moving if the cursor is already at the start
+    /// of the array.
+    pub fn move_prev(&mut self) -> bool {
+        if self.index > 0 {
+            self.index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Move the cursor to `index`.
+    ///
+    /// Panics if `index` is greater than the length of the array.
+    pub fn seek(&mut self, index: usize) {
+        if index > self.array.len() {
+            panic!(
+                "CursorMut::seek: index {} out of bounds for length {}",
+                index,
+                self.array.len()
+            );
+        }
+        self.index = index;
+    }
+
+    /// Get a reference to the element the cursor is positioned at, or `None`
+    /// if it's past the last element.
+    pub fn peek(&self) -> Option<&<Arr as Index<usize>>::Output> {
+        self.array.get(self.index)
+    }
+
+    /// Get a mutable reference to the element the cursor is positioned at,
+    /// or `None` if it's past the last element.
+    pub fn peek_mut(&mut self) -> Option<&mut <Arr as Index<usize>>::Output> {
+        self.array.get_mut(self.index)
+    }
+
+    /// Get a reference to the element after the cursor.
+    pub fn peek_next(&self) -> Option<&<Arr as Index<usize>>::Output> {
+        self.array.get(self.index + 1)
+    }
+
+    /// Get a reference to the element before the cursor.
+    pub fn peek_prev(&self) -> Option<&<Arr as Index<usize>>::Output> {
+        self.index
+            .checked_sub(1)
+            .and_then(|index| self.array.get(index))
+    }
+}
+
+impl<'a, Arr> CursorMut<'a, Arr>
+where
+    Arr: ArrayResize + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    /// Insert an element before the cursor.
+    ///
+    /// The cursor stays positioned at the same element, which has moved one
+    /// index up.
+    pub fn insert_before(&mut self, value: <Arr as Index<usize>>::Output) {
+        self.array.insert(self.index, value);
+        self.index += 1;
+    }
+
+    /// Remove the element the cursor is positioned at and return it.
+    ///
+    /// The cursor is left positioned at the element which followed the
+    /// removed one. Returns `None` if the cursor is past the last element.
+    pub fn remove_current(&mut self) -> Option<<Arr as Index<usize>>::Output> {
+        self.array.remove(self.index)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn cursor() {
+        let vec: VecDeque<_> = vec![1, 2, 3].into();
+        let mut cursor = Cursor::new(&vec);
+        assert_eq!(Some(&1), cursor.peek());
+        assert_eq!(None, cursor.peek_prev());
+        assert!(!cursor.move_prev());
+        assert!(cursor.move_next());
+        assert_eq!(Some(&1), cursor.peek_prev());
+        assert_eq!(Some(&2), cursor.peek());
+        assert_eq!(Some(&3), cursor.peek_next());
+        cursor.seek(3);
+        assert_eq!(None, cursor.peek());
+        assert_eq!(Some(&3), cursor.peek_prev());
+        assert!(!cursor.move_next());
+        assert_eq!(3, cursor.index());
+    }
+
+    #[test]
+    #[should_panic]
+    fn cursor_seek_out_of_bounds() {
+        let vec: VecDeque<_> = vec![1, 2, 3].into();
+        Cursor::new(&vec).seek(4);
+    }
+
+    #[test]
+    fn cursor_mut() {
+        let mut vec: VecDeque<_> = vec![1, 2, 3, 4].into();
+        let mut cursor = CursorMut::new(&mut vec);
+        while let Some(value) = cursor.peek_mut() {
+            if *value % 2 == 0 {
+                *value *= 10;
+                cursor.insert_before(0);
+                cursor.move_next();
+            } else if *value == 3 {
+                assert_eq!(Some(3), cursor.remove_current());
+            } else {
+                cursor.move_next();
+            }
+        }
+        assert_eq!(None, cursor.remove_current());
+        cursor.insert_before(5);
+        assert_eq!(Some(&5), cursor.as_cursor().peek_prev());
+        assert_eq!(vec![1, 0, 20, 0, 40, 5], Vec::from(vec));
+    }
+}
@@ -0,0 +1,28 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// An error returned when an array doesn't have the length an operation
+/// requires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LengthMismatch {
+    /// The length the operation required.
+    pub expected: usize,
+    /// The actual length of the array.
+    pub actual: usize,
+}
+
+impl Display for LengthMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected an array of length {} but found length {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl Error for LengthMismatch {}
@@ -0,0 +1,188 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cmp::Ordering;
+use std::ops::Index;
+
+use crate::array::{ArrayMut, ArrayResize, HasLength};
+
+/// Move the element at `index` up towards the root of the heap stored in
+/// `array` until the heap property is restored.
+pub(crate) fn sift_up<Arr, F>(array: &mut Arr, mut index: usize, cmp: &mut F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    while index > 0 {
+        let parent = (index - 1) / 2;
+        if cmp(&array[parent], &array[index]) != Ordering::Less {
+            break;
+        }
+        array.swap(parent, index);
+        index = parent;
+    }
+}
+
+/// Move the element at `index` down away from the root of the heap stored in
+/// the first `end` elements of `array` until the heap property is restored.
+pub(crate) fn sift_down<Arr, F>(array: &mut Arr, mut index: usize, end: usize, cmp: &mut F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    loop {
+        let mut child = 2 * index + 1;
+        if child >= end {
+            break;
+        }
+        if child + 1 < end && cmp(&array[child], &array[child + 1]) == Ordering::Less {
+            child += 1;
+        }
+        if cmp(&array[index], &array[child]) != Ordering::Less {
+            break;
+        }
+        array.swap(index, child);
+        index = child;
+    }
+}
+
+/// Rearrange the first `end` elements of `array` into a max-heap.
+pub(crate) fn heapify<Arr, F>(array: &mut Arr, end: usize, cmp: &mut F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    for index in (0..end / 2).rev() {
+        sift_down(array, index, end, cmp);
+    }
+}
+
+/// Sort `array` in place with heapsort, which takes O(n log n) time for any
+/// input and doesn't allocate.
+pub(crate) fn heapsort<Arr, F>(array: &mut Arr, cmp: &mut F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    let len = array.len();
+    heapify(array, len, cmp);
+    for end in (1..len).rev() {
+        array.swap(0, end);
+        sift_down(array, 0, end, cmp);
+    }
+}
+
+/// A priority queue backed by any `ArrayMut`.
+///
+/// The elements of the wrapped array are kept arranged as a binary max-heap,
+/// so that the greatest element is always available at the front, like a
+/// `std::collections::BinaryHeap`. If the wrapped array implements
+/// `ArrayResize`, elements can also be pushed onto and popped off the heap.
+#[derive(Clone, Debug)]
+pub struct HeapAdapter<Arr> {
+    array: Arr,
+}
+
+impl<Arr> HeapAdapter<Arr>
+where
+    Arr: ArrayMut,
+    <Arr as Index<usize>>::Output: Ord + Sized,
+{
+    /// Construct a heap from an array, rearranging its elements to satisfy
+    /// the heap property.
+    pub fn new(mut array: Arr) -> Self {
+        let len = array.len();
+        heapify(&mut array, len, &mut Ord::cmp);
+        HeapAdapter { array }
+    }
+
+    /// Get a reference to the greatest element in the heap.
+    pub fn peek(&self) -> Option<&<Arr as Index<usize>>::Output> {
+        self.array.first()
+    }
+
+    /// Get a reference to the wrapped array.
+    pub fn as_inner(&self) -> &Arr {
+        &self.array
+    }
+
+    /// Discard the heap and return the wrapped array, in heap order.
+    pub fn into_inner(self) -> Arr {
+        self.array
+    }
+}
+
+impl<Arr> HeapAdapter<Arr>
+where
+    Arr: ArrayResize,
+    <Arr as Index<usize>>::Output: Ord + Sized,
+{
+    /// Push an element onto the heap.
+    pub fn push(&mut self, value: <Arr as Index<usize>>::Output) {
+        self.array.push(value);
+        let last = self.array.len() - 1;
+        sift_up(&mut self.array, last, &mut Ord::cmp);
+    }
+
+    /// Remove the greatest element from the heap and return it.
+    pub fn pop(&mut self) -> Option<<Arr as Index<usize>>::Output> {
+        let len = self.array.len();
+        if len == 0 {
+            return None;
+        }
+        self.array.swap(0, len - 1);
+        let result = self.array.pop();
+        sift_down(&mut self.array, 0, len - 1, &mut Ord::cmp);
+        result
+    }
+}
+
+impl<Arr> HasLength for HeapAdapter<Arr>
+where
+    Arr: HasLength,
+{
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_core::{RngCore, SeedableRng};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn heap_adapter() {
+        let vec: VecDeque<_> = vec![5, 1, 8, 3, 9, 2].into();
+        let mut heap = HeapAdapter::new(vec);
+        assert_eq!(Some(&9), heap.peek());
+        heap.push(7);
+        heap.push(10);
+        assert_eq!(8, heap.len());
+        let mut out = Vec::new();
+        while let Some(value) = heap.pop() {
+            out.push(value);
+        }
+        assert_eq!(vec![10, 9, 8, 7, 5, 3, 2, 1], out);
+        assert!(heap.is_empty());
+        assert_eq!(None, heap.peek());
+    }
+
+    #[test]
+    fn heapsort() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        for len in 0..50 {
+            let mut vec: VecDeque<u64> = (0..len).map(|_| rng.next_u64() % 20).collect();
+            let mut expected: Vec<_> = vec.iter().cloned().collect();
+            expected.sort();
+            super::heapsort(&mut vec, &mut Ord::cmp);
+            assert_eq!(vec, expected);
+        }
+    }
+}
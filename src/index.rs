@@ -0,0 +1,175 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::convert::TryFrom;
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+use crate::array::{Array, ArrayMut, HasLength};
+
+/// Trait for integer types which can be used as array indexes.
+pub trait ArrayIndex: Copy {
+    /// Convert a `usize` into an index, or return `None` if it's not
+    /// representable in this type.
+    fn from_usize(index: usize) -> Option<Self>;
+
+    /// Convert the index into a `usize`.
+    fn into_usize(self) -> usize;
+}
+
+macro_rules! impl_array_index {
+    ($($t:ty),*) => {
+        $(
+            impl ArrayIndex for $t {
+                fn from_usize(index: usize) -> Option<Self> {
+                    <$t>::try_from(index).ok()
+                }
+
+                fn into_usize(self) -> usize {
+                    usize::try_from(self).expect("index does not fit in a usize")
+                }
+            }
+        )*
+    };
+}
+
+impl_array_index!(u8, u16, u32, u64, usize);
+
+/// An adapter which makes a data structure indexed by a type other than
+/// `usize` usable as an `Array`.
+///
+/// Implement `HasLength` and `Index<I>` (and `IndexMut<I>`) for your data
+/// structure, where `I` is an `ArrayIndex` such as `u32`, and `Reindexed`
+/// will provide `Index<usize>` on top of them, so the `Array` and `ArrayMut`
+/// methods are available. Indexes which aren't representable in `I` are out
+/// of bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reindexed<Arr, I> {
+    array: Arr,
+    index: PhantomData<fn(I)>,
+}
+
+impl<Arr, I> Reindexed<Arr, I>
+where
+    Arr: HasLength + Index<I>,
+    I: ArrayIndex,
+{
+    /// Wrap a data structure indexed by `I`.
+    pub fn new(array: Arr) -> Self {
+        Reindexed {
+            array,
+            index: PhantomData,
+        }
+    }
+
+    /// Get a reference to the wrapped data structure.
+    pub fn as_inner(&self) -> &Arr {
+        &self.array
+    }
+
+    /// Get a mutable reference to the wrapped data structure.
+    pub fn as_inner_mut(&mut self) -> &mut Arr {
+        &mut self.array
+    }
+
+    /// Discard the adapter and return the wrapped data structure.
+    pub fn into_inner(self) -> Arr {
+        self.array
+    }
+
+    fn convert(&self, index: usize) -> I {
+        match I::from_usize(index) {
+            Some(index) => index,
+            None => panic!(
+                "Reindexed::index: index {} out of bounds for length {}",
+                index,
+                self.array.len()
+            ),
+        }
+    }
+}
+
+impl<Arr, I> HasLength for Reindexed<Arr, I>
+where
+    Arr: HasLength,
+{
+    fn len(&self) -> usize {
+        self.array.len()
+    }
+}
+
+impl<Arr, I> Index<usize> for Reindexed<Arr, I>
+where
+    Arr: HasLength + Index<I>,
+    I: ArrayIndex,
+{
+    type Output = <Arr as Index<I>>::Output;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.array[self.convert(index)]
+    }
+}
+
+impl<Arr, I> IndexMut<usize> for Reindexed<Arr, I>
+where
+    Arr: HasLength + IndexMut<I>,
+    I: ArrayIndex,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let index = self.convert(index);
+        &mut self.array[index]
+    }
+}
+
+impl<Arr, I> Array for Reindexed<Arr, I>
+where
+    Arr: HasLength + Index<I>,
+    I: ArrayIndex,
+{
+}
+
+impl<Arr, I> ArrayMut for Reindexed<Arr, I>
+where
+    Arr: HasLength + IndexMut<I>,
+    I: ArrayIndex,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Arena(Vec<&'static str>);
+
+    impl HasLength for Arena {
+        fn len(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl Index<u16> for Arena {
+        type Output = &'static str;
+        fn index(&self, index: u16) -> &Self::Output {
+            &self.0[index as usize]
+        }
+    }
+
+    impl IndexMut<u16> for Arena {
+        fn index_mut(&mut self, index: u16) -> &mut Self::Output {
+            &mut self.0[index as usize]
+        }
+    }
+
+    #[test]
+    fn reindexed() {
+        let mut arena = Reindexed::new(Arena(vec!["c", "a", "b"]));
+        assert_eq!(Some(&"c"), arena.first());
+        assert_eq!(None, arena.get(70000));
+        arena.sort_unstable();
+        assert_eq!(vec!["a", "b", "c"], arena.into_inner().0);
+        assert_eq!(Some(7u8), u8::from_usize(7));
+        assert_eq!(None, u8::from_usize(256));
+        assert_eq!(65535, 65535u16.into_usize());
+    }
+}
@@ -0,0 +1,2191 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::{Add, Index, Range};
+use std::ptr::NonNull;
+
+use crate::array::{Array, DisjointIndexMut};
+use crate::slice::{ArraySlice, ArraySliceMut};
+
+/// An iterator over references to the elements of an array.
+///
+/// See `Array::iter`.
+pub struct Iter<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> Iter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        Iter {
+            array,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for Iter<'a, Arr> {
+    fn clone(&self) -> Self {
+        Iter {
+            array: self.array,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for Iter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = &'a <Arr as Index<usize>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let index = self.front;
+        self.front += 1;
+        Some(&self.array[index])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for Iter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(&self.array[self.back])
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n).max(self.front);
+        self.next_back()
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for Iter<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for Iter<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over mutable references to the elements of an array.
+///
+/// See `ArrayMut::iter_mut`.
+pub struct IterMut<'a, Arr: ?Sized> {
+    array: NonNull<Arr>,
+    front: usize,
+    back: usize,
+    lifetime: PhantomData<&'a mut Arr>,
+}
+
+impl<'a, Arr> IterMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    pub(crate) fn new(array: &'a mut Arr) -> Self {
+        IterMut {
+            front: 0,
+            back: array.len(),
+            array: array.into(),
+            lifetime: PhantomData,
+        }
+    }
+}
+
+unsafe impl<'a, Arr> Send for IterMut<'a, Arr> where Arr: Send + ?Sized {}
+unsafe impl<'a, Arr> Sync for IterMut<'a, Arr> where Arr: Sync + ?Sized {}
+
+impl<'a, Arr> Iterator for IterMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    type Item = &'a mut <Arr as Index<usize>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let index = self.front;
+        self.front += 1;
+        // Each index is yielded only once, and `DisjointIndexMut` promises
+        // that distinct indexes give pointers to distinct elements which stay
+        // valid without creating references to the array, so the references
+        // we hand out never overlap, and the array is mutably borrowed for
+        // `'a`.
+        unsafe { Some(&mut *Arr::get_unchecked_ptr_mut(self.array.as_ptr(), index)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for IterMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        let index = self.back;
+        // See `next`.
+        unsafe { Some(&mut *Arr::get_unchecked_ptr_mut(self.array.as_ptr(), index)) }
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.back = self.back.saturating_sub(n).max(self.front);
+        self.next_back()
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for IterMut<'a, Arr> where Arr: DisjointIndexMut + ?Sized {}
+
+impl<'a, Arr> FusedIterator for IterMut<'a, Arr> where Arr: DisjointIndexMut + ?Sized {}
+
+/// An iterator over the indexes of the elements of an array which match a
+/// predicate.
+///
+/// See `Array::positions`.
+pub struct Positions<'a, Arr: ?Sized, F> {
+    array: &'a Arr,
+    predicate: F,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr, F> Positions<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, predicate: F) -> Self {
+        Positions {
+            array,
+            predicate,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr, F> Iterator for Positions<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            let index = self.front;
+            self.front += 1;
+            if (self.predicate)(&self.array[index]) {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.back - self.front))
+    }
+}
+
+impl<'a, Arr, F> DoubleEndedIterator for Positions<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            self.back -= 1;
+            if (self.predicate)(&self.array[self.back]) {
+                return Some(self.back);
+            }
+        }
+        None
+    }
+}
+
+impl<'a, Arr, F> FusedIterator for Positions<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over the runs of consecutive equal elements in an array,
+/// yielding the length of each run together with its first element.
+///
+/// See `Array::iter_dedup_with_count`.
+pub struct DedupWithCount<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    index: usize,
+}
+
+impl<'a, Arr> DedupWithCount<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        DedupWithCount { array, index: 0 }
+    }
+}
+
+impl<'a, Arr> Iterator for DedupWithCount<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output: PartialEq,
+{
+    type Item = (usize, &'a <Arr as Index<usize>>::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        let start = self.index;
+        if start >= array.len() {
+            return None;
+        }
+        self.index += 1;
+        while self.index < array.len() && array[self.index] == array[start] {
+            self.index += 1;
+        }
+        Some((self.index - start, &array[start]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len() - self.index;
+        ((remaining > 0) as usize, Some(remaining))
+    }
+}
+
+impl<'a, Arr> FusedIterator for DedupWithCount<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output: PartialEq,
+{
+}
+
+/// The equality function `Array::iter_deduped` compares elements with.
+pub type EqFn<A> = fn(&A, &A) -> bool;
+
+/// An iterator over the elements of an array which skips consecutive
+/// duplicates.
+///
+/// See `Array::iter_deduped` and `Array::iter_deduped_by`.
+pub struct Deduped<'a, Arr: ?Sized, F> {
+    array: &'a Arr,
+    same_bucket: F,
+    index: usize,
+}
+
+impl<'a, Arr, F> Deduped<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, same_bucket: F) -> Self {
+        Deduped {
+            array,
+            same_bucket,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, Arr, F> Iterator for Deduped<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = &'a <Arr as Index<usize>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        let start = self.index;
+        if start >= array.len() {
+            return None;
+        }
+        self.index += 1;
+        while self.index < array.len() && (self.same_bucket)(&array[self.index], &array[start]) {
+            self.index += 1;
+        }
+        Some(&array[start])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.array.len() - self.index;
+        ((remaining > 0) as usize, Some(remaining))
+    }
+}
+
+impl<'a, Arr, F> FusedIterator for Deduped<'a, Arr, F>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over the ranges of integers missing from a sorted array of
+/// integers.
+///
+/// See `Array::find_gaps`.
+pub struct Gaps<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    index: usize,
+}
+
+impl<'a, Arr> Gaps<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        Gaps { array, index: 1 }
+    }
+}
+
+impl<'a, Arr> Iterator for Gaps<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output:
+        Copy + Ord + Add<Output = <Arr as Index<usize>>::Output> + From<bool>,
+{
+    type Item = Range<<Arr as Index<usize>>::Output>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        while self.index < array.len() {
+            let prev = array[self.index - 1];
+            let end = array[self.index];
+            self.index += 1;
+            // Only take the successor of an element smaller than the next
+            // one, so that it can't overflow.
+            if prev < end {
+                let start = prev + true.into();
+                if start < end {
+                    return Some(start..end);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.array.len().saturating_sub(self.index)))
+    }
+}
+
+impl<'a, Arr> FusedIterator for Gaps<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output:
+        Copy + Ord + Add<Output = <Arr as Index<usize>>::Output> + From<bool>,
+{
+}
+
+/// An iterator over the result of folding each window of an array.
+///
+/// See `Array::fold_windows`.
+pub struct FoldWindows<'a, Arr: ?Sized, B, F> {
+    array: &'a Arr,
+    size: usize,
+    index: usize,
+    init: B,
+    f: F,
+}
+
+impl<'a, Arr, B, F> FoldWindows<'a, Arr, B, F>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize, init: B, f: F) -> Self {
+        if size == 0 {
+            panic!("Array::fold_windows: window size cannot be zero!");
+        }
+        FoldWindows {
+            array,
+            size,
+            index: 0,
+            init,
+            f,
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        (self.array.len() + 1).saturating_sub(self.index + self.size)
+    }
+}
+
+impl<'a, Arr, B, F> Iterator for FoldWindows<'a, Arr, B, F>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    F: FnMut(B, &<Arr as Index<usize>>::Output) -> B,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining() == 0 {
+            return None;
+        }
+        let mut acc = self.init.clone();
+        for index in self.index..self.index + self.size {
+            acc = (self.f)(acc, &self.array[index]);
+        }
+        self.index += 1;
+        Some(acc)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr, B, F> ExactSizeIterator for FoldWindows<'a, Arr, B, F>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    F: FnMut(B, &<Arr as Index<usize>>::Output) -> B,
+{
+}
+
+impl<'a, Arr, B, F> FusedIterator for FoldWindows<'a, Arr, B, F>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    F: FnMut(B, &<Arr as Index<usize>>::Output) -> B,
+{
+}
+
+/// An iterator over a running accumulator for each window of an array,
+/// updated incrementally as elements enter and leave the window.
+///
+/// See `Array::fold_windows_incremental`.
+pub struct FoldWindowsIncremental<'a, Arr: ?Sized, B, Add, Remove> {
+    array: &'a Arr,
+    size: usize,
+    index: usize,
+    acc: B,
+    add: Add,
+    remove: Remove,
+}
+
+impl<'a, Arr, B, Add, Remove> FoldWindowsIncremental<'a, Arr, B, Add, Remove>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize, init: B, add: Add, remove: Remove) -> Self {
+        if size == 0 {
+            panic!("Array::fold_windows_incremental: window size cannot be zero!");
+        }
+        FoldWindowsIncremental {
+            array,
+            size,
+            index: 0,
+            acc: init,
+            add,
+            remove,
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        (self.array.len() + 1).saturating_sub(self.index + self.size)
+    }
+}
+
+impl<'a, Arr, B, Add, Remove> Iterator for FoldWindowsIncremental<'a, Arr, B, Add, Remove>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    Add: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+    Remove: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining() == 0 {
+            return None;
+        }
+        if self.index == 0 {
+            for index in 0..self.size {
+                (self.add)(&mut self.acc, &self.array[index]);
+            }
+        } else {
+            (self.remove)(&mut self.acc, &self.array[self.index - 1]);
+            (self.add)(&mut self.acc, &self.array[self.index + self.size - 1]);
+        }
+        self.index += 1;
+        Some(self.acc.clone())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr, B, Add, Remove> ExactSizeIterator for FoldWindowsIncremental<'a, Arr, B, Add, Remove>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    Add: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+    Remove: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+{
+}
+
+impl<'a, Arr, B, Add, Remove> FusedIterator for FoldWindowsIncremental<'a, Arr, B, Add, Remove>
+where
+    Arr: Array + ?Sized,
+    B: Clone,
+    Add: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+    Remove: FnMut(&mut B, &<Arr as Index<usize>>::Output),
+{
+}
+
+/// An iterator over the smallest or largest element of each window of an
+/// array.
+///
+/// See `Array::window_min` and `Array::window_max`.
+pub struct WindowExtremes<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    index: usize,
+    // Which way an element must compare against later ones to stay a
+    // candidate: `Less` for minimums and `Greater` for maximums.
+    keep: Ordering,
+    // Indexes of the candidate extremes of the current window, in ascending
+    // order, with their elements strictly monotonic in the `keep` direction.
+    candidates: VecDeque<usize>,
+}
+
+impl<'a, Arr> WindowExtremes<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize, keep: Ordering) -> Self {
+        if size == 0 {
+            let method = match keep {
+                Ordering::Greater => "window_max",
+                _ => "window_min",
+            };
+            panic!("Array::{}: window size cannot be zero!", method);
+        }
+        WindowExtremes {
+            array,
+            size,
+            index: 0,
+            keep,
+            candidates: VecDeque::new(),
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        (self.array.len() + 1).saturating_sub(self.index.max(self.size - 1) + 1)
+    }
+}
+
+impl<'a, Arr> Iterator for WindowExtremes<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output: Ord,
+{
+    type Item = &'a <Arr as Index<usize>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let array = self.array;
+        while self.index < array.len() {
+            let index = self.index;
+            self.index += 1;
+            while let Some(&last) = self.candidates.back() {
+                if array[last].cmp(&array[index]) == self.keep.reverse() {
+                    self.candidates.pop_back();
+                } else {
+                    break;
+                }
+            }
+            self.candidates.push_back(index);
+            if self.index >= self.size {
+                if self.candidates[0] + self.size < self.index {
+                    self.candidates.pop_front();
+                }
+                return Some(&array[self.candidates[0]]);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for WindowExtremes<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output: Ord,
+{
+}
+
+impl<'a, Arr> FusedIterator for WindowExtremes<'a, Arr>
+where
+    Arr: Array + ?Sized,
+    <Arr as Index<usize>>::Output: Ord,
+{
+}
+
+/// An iterator over each pair of adjacent elements of an array.
+///
+/// See `Array::pairs`.
+pub struct Pairs<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> Pairs<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        Pairs {
+            array,
+            front: 0,
+            back: array.len().saturating_sub(1),
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for Pairs<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = (
+        &'a <Arr as Index<usize>>::Output,
+        &'a <Arr as Index<usize>>::Output,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let (array, index) = (self.array, self.front);
+        self.front += 1;
+        Some((&array[index], &array[index + 1]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for Pairs<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        let (array, index) = (self.array, self.back);
+        Some((&array[index], &array[index + 1]))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for Pairs<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for Pairs<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over each triple of adjacent elements of an array.
+///
+/// See `Array::triples`.
+pub struct Triples<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> Triples<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        Triples {
+            array,
+            front: 0,
+            back: array.len().saturating_sub(2),
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for Triples<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = (
+        &'a <Arr as Index<usize>>::Output,
+        &'a <Arr as Index<usize>>::Output,
+        &'a <Arr as Index<usize>>::Output,
+    );
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let (array, index) = (self.array, self.front);
+        self.front += 1;
+        Some((&array[index], &array[index + 1], &array[index + 2]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for Triples<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        let (array, index) = (self.array, self.back);
+        Some((&array[index], &array[index + 1], &array[index + 2]))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for Triples<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for Triples<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of windows of an array which start a fixed number
+/// of elements apart.
+///
+/// See `Array::windows_step`.
+pub struct WindowsStep<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    step: usize,
+    index: usize,
+    partial: bool,
+    finished: bool,
+}
+
+impl<'a, Arr> WindowsStep<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize, step: usize) -> Self {
+        if size == 0 {
+            panic!("Array::windows_step: window size cannot be zero!");
+        }
+        if step == 0 {
+            panic!("Array::windows_step: step cannot be zero!");
+        }
+        WindowsStep {
+            array,
+            size,
+            step,
+            index: 0,
+            partial: false,
+            finished: false,
+        }
+    }
+
+    /// Also yield a final window shorter than the window size, if needed to
+    /// cover the elements at the end of the array which no full window
+    /// reaches.
+    pub fn with_partial(mut self) -> Self {
+        self.partial = true;
+        self
+    }
+
+    fn remaining(&self) -> usize {
+        let len = self.array.len();
+        if self.finished || self.index >= len {
+            return 0;
+        }
+        let full = if self.index + self.size <= len {
+            (len - self.index - self.size) / self.step + 1
+        } else {
+            0
+        };
+        let covered = if full > 0 {
+            self.index + (full - 1) * self.step + self.size
+        } else {
+            self.index
+        };
+        // A partial window is only yielded if the elements past the last full
+        // window aren't skipped over by the step.
+        let next = self.index + full * self.step;
+        full + (self.partial && covered < len && next < len) as usize
+    }
+}
+
+impl<'a, Arr> Iterator for WindowsStep<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let len = self.array.len();
+        if self.finished || self.index >= len {
+            return None;
+        }
+        let start = self.index;
+        let end = start + self.size;
+        if end > len && !self.partial {
+            self.finished = true;
+            return None;
+        }
+        let end = end.min(len);
+        self.finished = end == len;
+        self.index += self.step;
+        Some(ArraySlice::new(self.array, start..end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining();
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for WindowsStep<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for WindowsStep<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of the overlapping windows of a fixed size in an
+/// array.
+///
+/// See `Array::windows`.
+pub struct Windows<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> Windows<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("Array::windows: window size cannot be zero!");
+        }
+        Windows {
+            array,
+            size,
+            front: 0,
+            back: (array.len() + 1).saturating_sub(size),
+        }
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for Windows<'a, Arr> {
+    fn clone(&self) -> Self {
+        Windows {
+            array: self.array,
+            size: self.size,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for Windows<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front += 1;
+        Some(ArraySlice::new(self.array, start..start + self.size))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for Windows<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(ArraySlice::new(
+            self.array,
+            self.back..self.back + self.size,
+        ))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for Windows<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for Windows<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of non-overlapping chunks of an array, starting
+/// at the front.
+///
+/// See `Array::chunks`.
+pub struct Chunks<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> Chunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("Array::chunks: chunk size cannot be zero!");
+        }
+        Chunks {
+            array,
+            size,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for Chunks<'a, Arr> {
+    fn clone(&self) -> Self {
+        Chunks {
+            array: self.array,
+            size: self.size,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for Chunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front = self.back.min(start + self.size);
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front).div_ceil(self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for Chunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        // Only the last chunk can be short, so that's the one we take first
+        // from the back.
+        let end = self.back;
+        let short = (end - self.front) % self.size;
+        self.back = end - if short == 0 { self.size } else { short };
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for Chunks<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for Chunks<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over mutable views of non-overlapping chunks of an array,
+/// starting at the front.
+///
+/// The views are disjoint, so they can all be alive at the same time.
+///
+/// See `ArrayMut::chunks_mut`.
+pub struct ChunksMut<'a, Arr: ?Sized> {
+    array: NonNull<Arr>,
+    size: usize,
+    front: usize,
+    back: usize,
+    lifetime: PhantomData<&'a mut Arr>,
+}
+
+impl<'a, Arr> ChunksMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    pub(crate) fn new(array: &'a mut Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("ArrayMut::chunks_mut: chunk size cannot be zero!");
+        }
+        ChunksMut {
+            size,
+            front: 0,
+            back: array.len(),
+            array: array.into(),
+            lifetime: PhantomData,
+        }
+    }
+}
+
+unsafe impl<'a, Arr> Send for ChunksMut<'a, Arr> where Arr: DisjointIndexMut + Send + Sync + ?Sized {}
+unsafe impl<'a, Arr> Sync for ChunksMut<'a, Arr> where Arr: Sync + ?Sized {}
+
+impl<'a, Arr> Iterator for ChunksMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    type Item = ArraySliceMut<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front = self.back.min(start + self.size);
+        // The range is in bounds, no other chunk covers any of it, and
+        // `DisjointIndexMut` lets the chunks index the array at once.
+        unsafe { Some(ArraySliceMut::from_raw(self.array, start..self.front)) }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front).div_ceil(self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for ChunksMut<'a, Arr>
+where
+    Arr: DisjointIndexMut + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let end = self.back;
+        let short = (end - self.front) % self.size;
+        self.back = end - if short == 0 { self.size } else { short };
+        // See `next`.
+        unsafe { Some(ArraySliceMut::from_raw(self.array, self.back..end)) }
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for ChunksMut<'a, Arr> where Arr: DisjointIndexMut + ?Sized {}
+
+impl<'a, Arr> FusedIterator for ChunksMut<'a, Arr> where Arr: DisjointIndexMut + ?Sized {}
+
+/// An iterator over views of non-overlapping chunks of an array which are
+/// all exactly the same size, starting at the front.
+///
+/// The elements at the end of the array which don't fill a whole chunk are
+/// available from `ChunksExact::remainder`.
+///
+/// See `Array::chunks_exact`.
+pub struct ChunksExact<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> ChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("Array::chunks_exact: chunk size cannot be zero!");
+        }
+        let len = array.len();
+        ChunksExact {
+            array,
+            size,
+            front: 0,
+            back: len - len % size,
+        }
+    }
+
+    /// Get a view of the elements at the end of the array which don't fill
+    /// a whole chunk, and so are never yielded by the iterator.
+    pub fn remainder(&self) -> ArraySlice<'a, Arr> {
+        let len = self.array.len();
+        ArraySlice::new(self.array, len - len % self.size..len)
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for ChunksExact<'a, Arr> {
+    fn clone(&self) -> Self {
+        ChunksExact {
+            array: self.array,
+            size: self.size,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for ChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front += self.size;
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front) / self.size;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let skip = n.saturating_mul(self.size);
+        self.front = self.front.saturating_add(skip).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for ChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let end = self.back;
+        self.back -= self.size;
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for ChunksExact<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for ChunksExact<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of non-overlapping chunks of an array, starting
+/// at the back.
+///
+/// See `Array::rchunks`.
+pub struct RChunks<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> RChunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("Array::rchunks: chunk size cannot be zero!");
+        }
+        RChunks {
+            array,
+            size,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for RChunks<'a, Arr> {
+    fn clone(&self) -> Self {
+        RChunks {
+            array: self.array,
+            size: self.size,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for RChunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let end = self.back;
+        self.back = self.front.max(end.saturating_sub(self.size));
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front).div_ceil(self.size);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for RChunks<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        // Only the chunk at the front of the array can be short.
+        let start = self.front;
+        let short = (self.back - start) % self.size;
+        self.front = start + if short == 0 { self.size } else { short };
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for RChunks<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for RChunks<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of non-overlapping chunks of an array which are
+/// all exactly the same size, starting at the back.
+///
+/// The elements at the front of the array which don't fill a whole chunk
+/// are available from `RChunksExact::remainder`.
+///
+/// See `Array::rchunks_exact`.
+pub struct RChunksExact<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    size: usize,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> RChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, size: usize) -> Self {
+        if size == 0 {
+            panic!("Array::rchunks_exact: chunk size cannot be zero!");
+        }
+        RChunksExact {
+            array,
+            size,
+            front: array.len() % size,
+            back: array.len(),
+        }
+    }
+
+    /// Get a view of the elements at the front of the array which don't fill
+    /// a whole chunk, and so are never yielded by the iterator.
+    pub fn remainder(&self) -> ArraySlice<'a, Arr> {
+        ArraySlice::new(self.array, ..self.array.len() % self.size)
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for RChunksExact<'a, Arr> {
+    fn clone(&self) -> Self {
+        RChunksExact {
+            array: self.array,
+            size: self.size,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for RChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let end = self.back;
+        self.back -= self.size;
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.back - self.front) / self.size;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for RChunksExact<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front += self.size;
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for RChunksExact<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for RChunksExact<'a, Arr> where Arr: Array + ?Sized {}
+
+/// An iterator over views of the parts of an array separated by elements
+/// which match a predicate.
+///
+/// See `Array::split`.
+pub struct Split<'a, Arr: ?Sized, P> {
+    array: &'a Arr,
+    predicate: P,
+    front: usize,
+    back: usize,
+    finished: bool,
+}
+
+impl<'a, Arr, P> Split<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, predicate: P) -> Self {
+        Split {
+            array,
+            predicate,
+            front: 0,
+            back: array.len(),
+            finished: false,
+        }
+    }
+
+    /// Yield whatever hasn't been yielded yet as a single part.
+    fn finish(&mut self) -> Option<ArraySlice<'a, Arr>> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        Some(ArraySlice::new(self.array, self.front..self.back))
+    }
+}
+
+impl<'a, Arr, P> Iterator for Split<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let start = self.front;
+        match (start..self.back).find(|&index| (self.predicate)(&self.array[index])) {
+            Some(index) => {
+                self.front = index + 1;
+                Some(ArraySlice::new(self.array, start..index))
+            }
+            None => {
+                self.finished = true;
+                Some(ArraySlice::new(self.array, start..self.back))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            (0, Some(0))
+        } else {
+            (1, Some(self.back - self.front + 1))
+        }
+    }
+}
+
+impl<'a, Arr, P> DoubleEndedIterator for Split<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let end = self.back;
+        match (self.front..end)
+            .rev()
+            .find(|&index| (self.predicate)(&self.array[index]))
+        {
+            Some(index) => {
+                self.back = index;
+                Some(ArraySlice::new(self.array, index + 1..end))
+            }
+            None => {
+                self.finished = true;
+                Some(ArraySlice::new(self.array, self.front..end))
+            }
+        }
+    }
+}
+
+impl<'a, Arr, P> FusedIterator for Split<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over views of the parts of an array separated by elements
+/// which match a predicate, limited to a given number of parts.
+///
+/// See `Array::splitn`.
+pub struct SplitN<'a, Arr: ?Sized, P> {
+    inner: Split<'a, Arr, P>,
+    count: usize,
+}
+
+impl<'a, Arr, P> SplitN<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, count: usize, predicate: P) -> Self {
+        SplitN {
+            inner: Split::new(array, predicate),
+            count,
+        }
+    }
+}
+
+impl<'a, Arr, P> Iterator for SplitN<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.count {
+            0 => None,
+            1 => {
+                self.count = 0;
+                self.inner.finish()
+            }
+            _ => {
+                self.count -= 1;
+                self.inner.next()
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower, upper) = self.inner.size_hint();
+        let count = self.count;
+        (
+            lower.min(count),
+            Some(upper.map_or(count, |upper| upper.min(count))),
+        )
+    }
+}
+
+impl<'a, Arr, P> FusedIterator for SplitN<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over views of the parts of an array which end with elements
+/// matching a predicate.
+///
+/// See `Array::split_inclusive`.
+pub struct SplitInclusive<'a, Arr: ?Sized, P> {
+    array: &'a Arr,
+    predicate: P,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr, P> SplitInclusive<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, predicate: P) -> Self {
+        SplitInclusive {
+            array,
+            predicate,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr, P> Iterator for SplitInclusive<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front = (start..self.back)
+            .find(|&index| (self.predicate)(&self.array[index]))
+            .map_or(self.back, |index| index + 1);
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        ((remaining > 0) as usize, Some(remaining))
+    }
+}
+
+impl<'a, Arr, P> DoubleEndedIterator for SplitInclusive<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        // The last element ends this part whether it matches or not, so the
+        // search for the end of the previous part starts before it.
+        let end = self.back;
+        self.back = (self.front..end - 1)
+            .rev()
+            .find(|&index| (self.predicate)(&self.array[index]))
+            .map_or(self.front, |index| index + 1);
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+}
+
+impl<'a, Arr, P> FusedIterator for SplitInclusive<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over views of the runs of adjacent elements in an array
+/// which a predicate groups together.
+///
+/// See `Array::chunk_by`.
+pub struct ChunkBy<'a, Arr: ?Sized, P> {
+    array: &'a Arr,
+    predicate: P,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr, P> ChunkBy<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr, predicate: P) -> Self {
+        ChunkBy {
+            array,
+            predicate,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr, P> Iterator for ChunkBy<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> bool,
+{
+    type Item = ArraySlice<'a, Arr>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let start = self.front;
+        self.front += 1;
+        while self.front < self.back
+            && (self.predicate)(&self.array[self.front - 1], &self.array[self.front])
+        {
+            self.front += 1;
+        }
+        Some(ArraySlice::new(self.array, start..self.front))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        ((remaining > 0) as usize, Some(remaining))
+    }
+}
+
+impl<'a, Arr, P> DoubleEndedIterator for ChunkBy<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> bool,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let end = self.back;
+        self.back -= 1;
+        while self.back > self.front
+            && (self.predicate)(&self.array[self.back - 1], &self.array[self.back])
+        {
+            self.back -= 1;
+        }
+        Some(ArraySlice::new(self.array, self.back..end))
+    }
+}
+
+impl<'a, Arr, P> FusedIterator for ChunkBy<'a, Arr, P>
+where
+    Arr: Array + ?Sized,
+    P: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> bool,
+{
+}
+
+/// An iterator over the elements of an array and their indexes.
+///
+/// See `Array::indexed_iter`.
+pub struct IndexedIter<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    front: usize,
+    back: usize,
+}
+
+impl<'a, Arr> IndexedIter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    pub(crate) fn new(array: &'a Arr) -> Self {
+        IndexedIter {
+            array,
+            front: 0,
+            back: array.len(),
+        }
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for IndexedIter<'a, Arr> {
+    fn clone(&self) -> Self {
+        IndexedIter {
+            array: self.array,
+            front: self.front,
+            back: self.back,
+        }
+    }
+}
+
+impl<'a, Arr> Iterator for IndexedIter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Item = (usize, &'a <Arr as Index<usize>>::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        let index = self.front;
+        self.front += 1;
+        Some((index, &self.array[index]))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.back - self.front;
+        (remaining, Some(remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl<'a, Arr> DoubleEndedIterator for IndexedIter<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some((self.back, &self.array[self.back]))
+    }
+}
+
+impl<'a, Arr> ExactSizeIterator for IndexedIter<'a, Arr> where Arr: Array + ?Sized {}
+
+impl<'a, Arr> FusedIterator for IndexedIter<'a, Arr> where Arr: Array + ?Sized {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::array::ArrayMut;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn positions() {
+        let vec: VecDeque<_> = vec![1, 2, 3, 4, 5, 6].into();
+        let evens: Vec<_> = vec.positions(|n| n % 2 == 0).collect();
+        assert_eq!(vec![1, 3, 5], evens);
+        let mut iter = vec.positions(|n| n % 3 == 0);
+        assert_eq!(Some(5), iter.next_back());
+        assert_eq!(Some(2), iter.next());
+        assert_eq!(None, iter.next_back());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn dedup_with_count() {
+        let vec: VecDeque<_> = vec![1, 1, 2, 3, 3, 3, 1].into();
+        let runs: Vec<_> = vec.iter_dedup_with_count().collect();
+        assert_eq!(vec![(2, &1), (1, &2), (3, &3), (1, &1)], runs);
+        let empty: VecDeque<i32> = VecDeque::new();
+        assert_eq!(None, empty.iter_dedup_with_count().next());
+    }
+
+    #[test]
+    fn fold_windows() {
+        let vec: VecDeque<_> = vec![1, 2, 3, 4, 5].into();
+        let sums: Vec<_> = vec.fold_windows(3, 0, |acc, n| acc + n).collect();
+        assert_eq!(vec![6, 9, 12], sums);
+        let sums: Vec<_> = vec
+            .fold_windows_incremental(3, 0, |acc, n| *acc += n, |acc, n| *acc -= n)
+            .collect();
+        assert_eq!(vec![6, 9, 12], sums);
+        assert_eq!(1, vec.fold_windows(5, 0, |acc, n| acc + n).len());
+        assert_eq!(0, vec.fold_windows(6, 0, |acc, n| acc + n).len());
+    }
+
+    #[test]
+    fn deduped() {
+        let vec: VecDeque<_> = vec![1, 1, 2, 3, 3, 3, 1].into();
+        let out: Vec<_> = vec.iter_deduped().cloned().collect();
+        assert_eq!(vec![1, 2, 3, 1], out);
+        let vec: VecDeque<_> = vec![1, 2, 4, 5, 7, 8].into();
+        let out: Vec<_> = vec
+            .iter_deduped_by(|a, b| a / 3 == b / 3)
+            .cloned()
+            .collect();
+        assert_eq!(vec![1, 4, 7], out);
+        let empty: VecDeque<u8> = VecDeque::new();
+        assert_eq!(None, empty.iter_deduped().next());
+    }
+
+    #[test]
+    fn gaps() {
+        let vec: VecDeque<u32> = vec![1, 2, 5, 5, 6, 10, 11].into();
+        let gaps: Vec<_> = vec.find_gaps().collect();
+        assert_eq!(vec![3..5, 7..10], gaps);
+        let vec: VecDeque<i64> = vec![-3, 0, 1].into();
+        let gaps: Vec<_> = vec.find_gaps().collect();
+        assert_eq!(vec![-2..0], gaps);
+        let vec: VecDeque<u8> = vec![254, 255].into();
+        assert_eq!(None, vec.find_gaps().next());
+        let vec: VecDeque<u8> = vec![1, 255, 255].into();
+        let gaps: Vec<_> = vec.find_gaps().collect();
+        assert_eq!(vec![2..255], gaps);
+        let empty: VecDeque<u8> = VecDeque::new();
+        assert_eq!(None, empty.find_gaps().next());
+    }
+
+    #[test]
+    fn window_extremes() {
+        let vec: VecDeque<_> = vec![4, 2, 12, 3, 8, 8, 1, 5].into();
+        let mins = vec.window_min(3);
+        assert_eq!(6, mins.len());
+        assert_eq!(vec![2, 2, 3, 3, 1, 1], mins.cloned().collect::<Vec<_>>());
+        let maxes: Vec<_> = vec.window_max(3).cloned().collect();
+        assert_eq!(vec![12, 12, 12, 8, 8, 8], maxes);
+        let ones: Vec<_> = vec.window_max(1).cloned().collect();
+        assert_eq!(Vec::from(vec.clone()), ones);
+        assert_eq!(None, vec.window_min(9).next());
+        assert_eq!(0, vec.window_min(9).len());
+    }
+
+    #[test]
+    fn pairs_and_triples() {
+        let vec: VecDeque<_> = vec![1, 3, 6, 10].into();
+        let deltas: Vec<_> = vec.pairs().map(|(a, b)| b - a).collect();
+        assert_eq!(vec![2, 3, 4], deltas);
+        assert_eq!(Some((&6, &10)), vec.pairs().next_back());
+        let triples: Vec<_> = vec.triples().rev().collect();
+        assert_eq!(vec![(&3, &6, &10), (&1, &3, &6)], triples);
+        assert_eq!(2, vec.triples().len());
+        let single: VecDeque<_> = vec![1].into();
+        assert_eq!(0, single.pairs().len());
+        assert_eq!(None, single.triples().next());
+    }
+
+    #[test]
+    fn windows_step() {
+        let vec: VecDeque<_> = (0..11).collect();
+        let ranges = |iter: WindowsStep<'_, VecDeque<i32>>| {
+            assert_eq!(iter.len(), iter.size_hint().0);
+            let len = iter.len();
+            let ranges: Vec<_> = iter.map(|window| window.range()).collect();
+            assert_eq!(len, ranges.len());
+            ranges
+        };
+        assert_eq!(vec![0..4, 3..7, 6..10], ranges(vec.windows_step(4, 3)));
+        assert_eq!(
+            vec![0..4, 3..7, 6..10, 9..11],
+            ranges(vec.windows_step(4, 3).with_partial())
+        );
+        assert_eq!(vec![0..4, 5..9], ranges(vec.windows_step(4, 5)));
+        assert_eq!(
+            vec![0..4, 5..9, 10..11],
+            ranges(vec.windows_step(4, 5).with_partial())
+        );
+        assert_eq!(
+            vec![0..5, 2..7, 4..9, 6..11],
+            ranges(vec.windows_step(5, 2).with_partial())
+        );
+        assert_eq!(
+            Vec::<std::ops::Range<usize>>::new(),
+            ranges(vec.windows_step(12, 1))
+        );
+        assert_eq!(vec![0..11], ranges(vec.windows_step(12, 1).with_partial()));
+        let ten: VecDeque<_> = (0..10).collect();
+        assert_eq!(
+            vec![0..2, 5..7],
+            ranges(ten.windows_step(2, 5).with_partial())
+        );
+        let five: VecDeque<_> = (0..5).collect();
+        assert_eq!(vec![0..2], ranges(five.windows_step(2, 5).with_partial()));
+        let window = vec.windows_step(4, 3).nth(1).unwrap();
+        assert_eq!(Some(&5), window.get(2));
+    }
+
+    #[test]
+    fn indexed_iter() {
+        let vec: VecDeque<_> = vec!['a', 'b', 'c', 'd'].into();
+        let mut iter = vec.indexed_iter();
+        assert_eq!(4, iter.len());
+        assert_eq!(Some((0, &'a')), iter.next());
+        assert_eq!(Some((3, &'d')), iter.next_back());
+        assert_eq!(2, iter.len());
+        let rest: Vec<_> = iter.rev().collect();
+        assert_eq!(vec![(2, &'c'), (1, &'b')], rest);
+        assert_eq!(Some((2, &'c')), vec.indexed_iter().nth(2));
+        assert_eq!(None, vec.indexed_iter().nth(4));
+    }
+
+    #[test]
+    fn iter() {
+        let vec: VecDeque<_> = vec![1, 2, 3, 4, 5].into();
+        let mut iter = Array::iter(&vec);
+        assert_eq!((5, Some(5)), iter.size_hint());
+        assert_eq!(Some(&1), iter.next());
+        assert_eq!(Some(&4), iter.nth(2));
+        assert_eq!((1, Some(1)), iter.size_hint());
+        assert_eq!(vec![&5], iter.collect::<Vec<_>>());
+        assert_eq!(15, vec.slice(..).iter().sum::<i32>());
+        assert_eq!(None, vec.slice(..0).iter().next());
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut vec: VecDeque<_> = vec![1, 2, 3, 4, 5].into();
+        let mut iter = ArrayMut::iter_mut(&mut vec);
+        let first = iter.next().unwrap();
+        let third = iter.nth(1).unwrap();
+        std::mem::swap(first, third);
+        assert_eq!((2, Some(2)), iter.size_hint());
+        for value in iter {
+            *value *= 10;
+        }
+        assert_eq!(vec, vec![3, 2, 1, 40, 50]);
+    }
+
+    #[test]
+    fn iter_double_ended() {
+        let mut vec: VecDeque<_> = vec![1, 2, 3, 4, 5].into();
+        let mut iter = Array::iter(&vec);
+        assert_eq!(Some(&5), iter.next_back());
+        assert_eq!(Some(&1), iter.next());
+        assert_eq!(3, iter.len());
+        assert_eq!(Some(&2), iter.nth_back(2));
+        assert_eq!(None, iter.nth_back(0));
+        assert_eq!(None, iter.next());
+        assert_eq!(
+            vec![&5, &4, &3, &2, &1],
+            Array::iter(&vec).rev().collect::<Vec<_>>()
+        );
+        let mut iter = ArrayMut::iter_mut(&mut vec);
+        let last = iter.next_back().unwrap();
+        let first = iter.next().unwrap();
+        std::mem::swap(first, last);
+        assert_eq!(3, iter.len());
+        for (index, value) in iter.rev().enumerate() {
+            *value = index;
+        }
+        assert_eq!(vec, vec![5, 2, 1, 0, 1]);
+    }
+
+    #[test]
+    fn windows() {
+        let vec: VecDeque<_> = vec![1, 2, 3, 4].into();
+        let ranges: Vec<_> = vec.windows(2).map(|window| window.range()).collect();
+        assert_eq!(vec![0..2, 1..3, 2..4], ranges);
+        let mut windows = vec.windows(3);
+        assert_eq!(2, windows.len());
+        assert_eq!(Some(1..4), windows.next_back().map(|window| window.range()));
+        assert_eq!(Some(0..3), windows.next().map(|window| window.range()));
+        assert!(windows.next().is_none());
+        assert_eq!(1, vec.windows(4).count());
+        assert_eq!(0, vec.windows(5).count());
+        assert_eq!(
+            vec![3, 5, 7],
+            vec.windows(2)
+                .map(|window| window[0] + window[1])
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn windows_size_zero() {
+        let vec: VecDeque<i32> = VecDeque::new();
+        vec.windows(0);
+    }
+
+    #[test]
+    fn chunks() {
+        let vec: VecDeque<_> = (0..7).collect();
+        let ranges: Vec<_> = vec.chunks(3).map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![0..3, 3..6, 6..7], ranges);
+        let ranges: Vec<_> = vec.chunks(3).rev().map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![6..7, 3..6, 0..3], ranges);
+        let mut chunks = vec.chunks(2);
+        assert_eq!(4, chunks.len());
+        assert_eq!(Some(0..2), chunks.next().map(|chunk| chunk.range()));
+        assert_eq!(Some(6..7), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(4..6), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(1, chunks.len());
+        assert_eq!(1, vec.chunks(7).count());
+        assert_eq!(1, vec.chunks(10).count());
+        assert_eq!(0, vec.slice(..0).chunks(3).count());
+    }
+
+    #[test]
+    fn chunks_exact() {
+        let vec: VecDeque<_> = (0..8).collect();
+        let chunks = vec.chunks_exact(3);
+        assert_eq!(6..8, chunks.remainder().range());
+        let ranges: Vec<_> = chunks.map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![0..3, 3..6], ranges);
+        let mut chunks = vec.chunks_exact(2);
+        assert_eq!(4, chunks.len());
+        assert_eq!(Some(6..8), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(4..6), chunks.nth(2).map(|chunk| chunk.range()));
+        assert!(chunks.next().is_none());
+        assert_eq!(8..8, chunks.remainder().range());
+        let chunks = vec.chunks_exact(10);
+        assert_eq!(0, chunks.len());
+        assert_eq!(0..8, chunks.remainder().range());
+    }
+
+    #[test]
+    fn rchunks() {
+        let vec: VecDeque<_> = (0..7).collect();
+        let ranges: Vec<_> = vec.rchunks(3).map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![4..7, 1..4, 0..1], ranges);
+        let ranges: Vec<_> = vec.rchunks(3).rev().map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![0..1, 1..4, 4..7], ranges);
+        let mut chunks = vec.rchunks(2);
+        assert_eq!(4, chunks.len());
+        assert_eq!(Some(0..1), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(5..7), chunks.next().map(|chunk| chunk.range()));
+        assert_eq!(2, chunks.len());
+        assert_eq!(0, vec.slice(..0).rchunks(3).count());
+
+        let chunks = vec.rchunks_exact(3);
+        assert_eq!(0..1, chunks.remainder().range());
+        let ranges: Vec<_> = chunks.map(|chunk| chunk.range()).collect();
+        assert_eq!(vec![4..7, 1..4], ranges);
+        let mut chunks = vec.rchunks_exact(2);
+        assert_eq!(3, chunks.len());
+        assert_eq!(Some(1..3), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(5..7), chunks.next().map(|chunk| chunk.range()));
+        assert_eq!(Some(3..5), chunks.next().map(|chunk| chunk.range()));
+        assert!(chunks.next().is_none());
+        assert_eq!(0..7, vec.rchunks_exact(8).remainder().range());
+    }
+
+    #[test]
+    fn chunks_mut() {
+        let mut vec: VecDeque<_> = (0..7).collect();
+        let mut chunks: Vec<_> = ArrayMut::chunks_mut(&mut vec, 3).collect();
+        assert_eq!(3, chunks.len());
+        let (first, rest) = chunks.split_at_mut(1);
+        crate::array::swap_between(&mut first[0], 0, &mut rest[1], 0);
+        for chunk in chunks.iter_mut() {
+            chunk.sort_unstable_desc();
+        }
+        assert_eq!(vec, vec![6, 2, 1, 5, 4, 3, 0]);
+        let mut chunks = ArrayMut::chunks_mut(&mut vec, 2);
+        assert_eq!(4, chunks.len());
+        assert_eq!(Some(6..7), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(4..6), chunks.next_back().map(|chunk| chunk.range()));
+        assert_eq!(Some(0..2), chunks.next().map(|chunk| chunk.range()));
+    }
+
+    #[test]
+    fn split() {
+        let vec: VecDeque<_> = vec![1, 0, 2, 3, 0, 0, 4].into();
+        let ranges: Vec<_> = vec.split(|&n| n == 0).map(|part| part.range()).collect();
+        assert_eq!(vec![0..1, 2..4, 5..5, 6..7], ranges);
+        let ranges: Vec<_> = vec
+            .split(|&n| n == 0)
+            .rev()
+            .map(|part| part.range())
+            .collect();
+        assert_eq!(vec![6..7, 5..5, 2..4, 0..1], ranges);
+        let mut parts = vec.split(|&n| n == 0);
+        assert_eq!(Some(0..1), parts.next().map(|part| part.range()));
+        assert_eq!(Some(6..7), parts.next_back().map(|part| part.range()));
+        assert_eq!(Some(2..4), parts.next().map(|part| part.range()));
+        assert_eq!(Some(5..5), parts.next_back().map(|part| part.range()));
+        assert!(parts.next().is_none());
+        assert!(parts.next_back().is_none());
+        let ranges: Vec<_> = vec.split(|&n| n == 4).map(|part| part.range()).collect();
+        assert_eq!(vec![0..6, 7..7], ranges);
+        assert_eq!(1, vec.slice(..0).split(|&n| n == 0).count());
+    }
+
+    #[test]
+    fn splitn() {
+        let vec: VecDeque<_> = vec![1, 0, 2, 3, 0, 0, 4].into();
+        let ranges = |count| {
+            vec.splitn(count, |&n| n == 0)
+                .map(|part| part.range())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(Vec::<Range<usize>>::new(), ranges(0));
+        assert_eq!(vec![0..7], ranges(1));
+        assert_eq!(vec![0..1, 2..7], ranges(2));
+        assert_eq!(vec![0..1, 2..4, 5..7], ranges(3));
+        assert_eq!(vec![0..1, 2..4, 5..5, 6..7], ranges(4));
+        assert_eq!(vec![0..1, 2..4, 5..5, 6..7], ranges(10));
+        assert_eq!((1, Some(2)), vec.splitn(2, |&n| n == 0).size_hint());
+    }
+
+    #[test]
+    fn split_inclusive() {
+        let vec: VecDeque<_> = vec![1, 0, 2, 3, 0, 0, 4].into();
+        let ranges: Vec<_> = vec
+            .split_inclusive(|&n| n == 0)
+            .map(|part| part.range())
+            .collect();
+        assert_eq!(vec![0..2, 2..5, 5..6, 6..7], ranges);
+        let ranges: Vec<_> = vec
+            .split_inclusive(|&n| n == 0)
+            .rev()
+            .map(|part| part.range())
+            .collect();
+        assert_eq!(vec![6..7, 5..6, 2..5, 0..2], ranges);
+        let trimmed = vec.slice(..6);
+        let ranges: Vec<_> = trimmed
+            .split_inclusive(|&n| n == 0)
+            .map(|part| part.range())
+            .collect();
+        assert_eq!(vec![0..2, 2..5, 5..6], ranges);
+        let mut parts = trimmed.split_inclusive(|&n| n == 0);
+        assert_eq!(Some(5..6), parts.next_back().map(|part| part.range()));
+        assert_eq!(Some(0..2), parts.next().map(|part| part.range()));
+        assert_eq!(Some(2..5), parts.next_back().map(|part| part.range()));
+        assert!(parts.next().is_none());
+        assert_eq!(0, vec.slice(..0).split_inclusive(|&n| n == 0).count());
+    }
+
+    #[test]
+    fn chunk_by() {
+        let vec: VecDeque<_> = vec![1, 1, 2, 3, 3, 3, 1].into();
+        let ranges: Vec<_> = vec.chunk_by(|l, r| l == r).map(|run| run.range()).collect();
+        assert_eq!(vec![0..2, 2..3, 3..6, 6..7], ranges);
+        let ranges: Vec<_> = vec
+            .chunk_by(|l, r| l <= r)
+            .rev()
+            .map(|run| run.range())
+            .collect();
+        assert_eq!(vec![6..7, 0..6], ranges);
+        let mut runs = vec.chunk_by(|l, r| l == r);
+        assert_eq!(Some(6..7), runs.next_back().map(|run| run.range()));
+        assert_eq!(Some(0..2), runs.next().map(|run| run.range()));
+        assert_eq!(Some(3..6), runs.next_back().map(|run| run.range()));
+        assert_eq!(Some(2..3), runs.next().map(|run| run.range()));
+        assert!(runs.next_back().is_none());
+        assert_eq!(0, vec.slice(..0).chunk_by(|l, r| l == r).count());
+    }
+}
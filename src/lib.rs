@@ -31,13 +31,33 @@
 //! `get_unchecked` and `get_unchecked_mut` implementations with bounds
 //! checking added is almost always going to be better than the
 //! default implementation, which adds bounds checking to an `index` call,
-//! most likely leading to bounds being checked twice.
+//! most likely leading to bounds being checked twice. Likewise, the sorting
+//! methods check the bounds of the range they're sorting once and then go
+//! through `get_unchecked` and `get_unchecked_mut`, which you should
+//! override with genuinely unchecked accessors if you can.
 //!
-//! The sorting algorithm provided is an implementation of optimal quicksort
-//! with randomised pivots, which should be a safe choice for any array-like, but
-//! there may well be better algoritms available for your particular data type.
-//! In particular, the quicksort isn't stable, which is why `ArrayMut` only
-//! provides `sort_unstable` and not `sort`.
+//! The unstable sorting algorithm provided is an introsort: an implementation
+//! of optimal quicksort with randomised pivots, which switches to insertion
+//! sort for short ranges and to heapsort if partitioning goes badly, so it
+//! runs in O(n log n) time even in the worst case. This should be a safe
+//! choice for any array-like, but there may well be better algoritms
+//! available for your particular data type. The stable `sort` is a merge
+//! sort, which needs to allocate a scratch buffer, so prefer `sort_unstable`
+//! unless you need equal elements to keep their order.
+//!
+//! # Panic Safety
+//!
+//! Every sorting method in this crate is panic safe: if the comparator or key
+//! extractor function panics, the sort is abandoned, and the array is left
+//! containing exactly the elements it contained before the sort, though in an
+//! unspecified order. No element is ever dropped twice or lost.
+//!
+//! # Feature Flags
+//!
+//! | Feature | Description |
+//! | ------- | ----------- |
+//! | `rayon` | Parallel methods using the [rayon](https://docs.rs/rayon) thread pool, such as `ArrayMut::par_for_each_chunk_mut`. |
+//! | `validate-ordering` | In debug builds, check the comparators passed to sorting and searching methods for consistency with a total order, and panic with a diagnostic if they're inconsistent. Binary searches on large arrays only check a fixed number of evenly spaced elements, so they stay logarithmic. |
 //!
 //! # Example
 //!
@@ -89,7 +109,32 @@
 #![cfg_attr(test, deny(warnings))]
 
 mod array;
+mod cursor;
+mod error;
+mod heap;
+mod index;
+mod iter;
+mod merge;
+mod slice;
 mod sort;
+mod stats;
 mod std_types;
+mod try_array;
+#[cfg(all(feature = "validate-ordering", debug_assertions))]
+mod validate;
+mod wrapping;
 
 pub use self::array::*;
+pub use self::cursor::{Cursor, CursorMut};
+pub use self::error::LengthMismatch;
+pub use self::heap::HeapAdapter;
+pub use self::index::{ArrayIndex, Reindexed};
+pub use self::iter::*;
+pub use self::slice::{ArraySlice, ArraySliceMut, ChunkSplit, NthSplit};
+pub use self::sort::{
+    FirstPivot, MedianOfThree, Ninther, PivotChoice, PivotStrategy, RandomPivot, SortAlgorithm,
+    SortOptions, SortSpec,
+};
+pub use self::stats::{ArrayStats, AsF64, Summary};
+pub use self::try_array::TryArray;
+pub use self::wrapping::Wrapping;
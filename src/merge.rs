@@ -0,0 +1,290 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cmp::Ordering;
+use std::mem::ManuallyDrop;
+use std::ops::Index;
+
+use crate::array::ArrayMut;
+
+pub(crate) fn check_merge_bounds(start: usize, mid: usize, end: usize, len: usize) {
+    if start > mid || mid > end || end > len {
+        panic!(
+            "invalid merge ranges {}..{} and {}..{} for length {}",
+            start, mid, mid, end, len
+        );
+    }
+}
+
+/// Holds the left run of a buffered merge, and moves whatever is left of it
+/// back into the array when dropped, so that the array is left intact if the
+/// comparator panics.
+struct MergeGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    array: &'a mut Arr,
+    buffer: &'a mut Vec<<Arr as Index<usize>>::Output>,
+    // Elements `buffer[taken..]` haven't been moved back into the array yet.
+    taken: usize,
+    // The next index in the array to be filled. Every index from here up to
+    // `out + buffer.len() - taken` is a hole.
+    out: usize,
+}
+
+impl<'a, Arr> MergeGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    /// Move the rest of the buffer into the holes in the array.
+    ///
+    /// Each hole's pointer comes from `index_mut` before the element is
+    /// moved, and the guard's state is updated after each move, so if
+    /// `index_mut` panics, dropping the guard carries on where this left off.
+    fn fill(&mut self) {
+        while self.taken < self.buffer.len() {
+            unsafe {
+                let hole: *mut <Arr as Index<usize>>::Output = &mut self.array[self.out];
+                std::ptr::copy_nonoverlapping(&self.buffer[self.taken], hole, 1);
+            }
+            self.taken += 1;
+            self.out += 1;
+        }
+        unsafe { self.buffer.set_len(0) };
+    }
+}
+
+impl<'a, Arr> Drop for MergeGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    fn drop(&mut self) {
+        self.fill();
+    }
+}
+
+/// Stably merge the sorted ranges `start..mid` and `mid..end`, using a
+/// temporary buffer the size of the first range.
+pub(crate) fn merge_buffered<Arr, F>(
+    array: &mut Arr,
+    start: usize,
+    mid: usize,
+    end: usize,
+    cmp: &mut F,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    if start == mid || mid == end {
+        return;
+    }
+    merge_with_buffer(
+        array,
+        start,
+        mid,
+        end,
+        cmp,
+        &mut Vec::with_capacity(mid - start),
+    )
+}
+
+/// Stably merge the sorted ranges `start..mid` and `mid..end`, using
+/// `buffer`, which must be empty, to hold the first range. The buffer is left
+/// empty afterwards, but keeps its capacity, so it can be reused for
+/// further merges.
+pub(crate) fn merge_with_buffer<Arr, F>(
+    array: &mut Arr,
+    start: usize,
+    mid: usize,
+    end: usize,
+    cmp: &mut F,
+    buffer: &mut Vec<<Arr as Index<usize>>::Output>,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    debug_assert!(buffer.is_empty());
+    if start == mid || mid == end {
+        return;
+    }
+    // Every element is read through a pointer from `index_mut`, and each
+    // pointer is used right after it's obtained: an element taken from the
+    // right run is read into a local before the hole's pointer is taken. The
+    // guard is in place from the start, so the buffer is moved back into the
+    // array if `index_mut` panics, while the local's copy is forgotten and
+    // the original stays in the right run.
+    buffer.reserve(mid - start);
+    let mut guard = MergeGuard {
+        array,
+        buffer,
+        taken: 0,
+        out: start,
+    };
+    for index in start..mid {
+        unsafe {
+            let value: *mut <Arr as Index<usize>>::Output = &mut guard.array[index];
+            guard.buffer.push(std::ptr::read(value));
+        }
+    }
+    let mut right = mid;
+    while guard.taken < guard.buffer.len() && right < end {
+        let take_right = cmp(&guard.array[right], &guard.buffer[guard.taken]) == Ordering::Less;
+        unsafe {
+            if take_right {
+                let value: *mut <Arr as Index<usize>>::Output = &mut guard.array[right];
+                let value = ManuallyDrop::new(std::ptr::read(value));
+                let hole: *mut <Arr as Index<usize>>::Output = &mut guard.array[guard.out];
+                std::ptr::write(hole, ManuallyDrop::into_inner(value));
+                right += 1;
+            } else {
+                let hole: *mut <Arr as Index<usize>>::Output = &mut guard.array[guard.out];
+                std::ptr::copy_nonoverlapping(&guard.buffer[guard.taken], hole, 1);
+                guard.taken += 1;
+            }
+        }
+        guard.out += 1;
+    }
+    // Move the rest of the buffer into place. Anything left of the right run
+    // is already where it belongs.
+    guard.fill();
+}
+
+/// Reverse the elements in `start..end`.
+pub(crate) fn reverse<Arr>(array: &mut Arr, mut start: usize, mut end: usize)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    while start + 1 < end {
+        end -= 1;
+        array.swap(start, end);
+        start += 1;
+    }
+}
+
+/// Rotate the elements in `start..end` so that the element at `mid` ends up
+/// at `start`.
+pub(crate) fn rotate<Arr>(array: &mut Arr, start: usize, mid: usize, end: usize)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    reverse(array, start, mid);
+    reverse(array, mid, end);
+    reverse(array, start, end);
+}
+
+/// Stably merge the sorted ranges `start..mid` and `mid..end` without
+/// allocating, by recursively splitting the ranges and rotating them into
+/// place. Takes O(n log n) swaps.
+pub(crate) fn merge_unbuffered<Arr, F>(
+    array: &mut Arr,
+    start: usize,
+    mid: usize,
+    end: usize,
+    cmp: &mut F,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    if start == mid || mid == end {
+        return;
+    }
+    if end - start == 2 {
+        if cmp(&array[mid], &array[start]) == Ordering::Less {
+            array.swap(start, mid);
+        }
+        return;
+    }
+    let (cut1, cut2) = if mid - start > end - mid {
+        // Find where the middle of the left run goes in the right run.
+        let cut1 = start + (mid - start) / 2;
+        let (mut low, mut high) = (mid, end);
+        while low < high {
+            let probe = low + (high - low) / 2;
+            if cmp(&array[probe], &array[cut1]) == Ordering::Less {
+                low = probe + 1;
+            } else {
+                high = probe;
+            }
+        }
+        (cut1, low)
+    } else {
+        // Find where the middle of the right run goes in the left run.
+        let cut2 = mid + (end - mid) / 2;
+        let (mut low, mut high) = (start, mid);
+        while low < high {
+            let probe = low + (high - low) / 2;
+            if cmp(&array[cut2], &array[probe]) == Ordering::Less {
+                high = probe;
+            } else {
+                low = probe + 1;
+            }
+        }
+        (low, cut2)
+    };
+    rotate(array, cut1, mid, cut2);
+    let new_mid = cut1 + (cut2 - mid);
+    merge_unbuffered(array, start, cut1, new_mid, cmp);
+    merge_unbuffered(array, new_mid, cut2, end, cmp);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand_core::{RngCore, SeedableRng};
+    use std::collections::VecDeque;
+
+    #[test]
+    fn merges() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        for total in 0..40 {
+            for mid in 0..=total {
+                let mut left: Vec<_> = (0..mid).map(|_| (rng.next_u64() % 8, 0)).collect();
+                let mut right: Vec<_> = (mid..total).map(|_| (rng.next_u64() % 8, 1)).collect();
+                left.sort();
+                right.sort();
+                let input: VecDeque<_> = left.iter().chain(right.iter()).cloned().collect();
+                let mut expected: Vec<_> = input.iter().cloned().collect();
+                // Stability means elements from the left run come first.
+                expected.sort();
+                let mut cmp = |l: &(u64, u8), r: &(u64, u8)| l.0.cmp(&r.0);
+                let mut buffered = input.clone();
+                merge_buffered(&mut buffered, 0, mid, total, &mut cmp);
+                assert_eq!(expected, Vec::from(buffered));
+                let mut unbuffered = input.clone();
+                merge_unbuffered(&mut unbuffered, 0, mid, total, &mut cmp);
+                assert_eq!(expected, Vec::from(unbuffered));
+            }
+        }
+    }
+
+    #[test]
+    fn merge_buffered_panic_safety() {
+        let original: VecDeque<_> = (0..10).chain(0..10).map(|n| n.to_string()).collect();
+        let mut vec = original.clone();
+        let mut count = 0;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            merge_buffered(&mut vec, 0, 10, 20, &mut |l: &String, r: &String| {
+                count += 1;
+                if count > 5 {
+                    panic!("comparator panic");
+                }
+                l.cmp(r)
+            })
+        }));
+        assert!(result.is_err());
+        let mut remaining = Vec::from(vec);
+        let mut expected = Vec::from(original);
+        remaining.sort();
+        expected.sort();
+        assert_eq!(expected, remaining);
+    }
+}
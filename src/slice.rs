@@ -0,0 +1,329 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::marker::PhantomData;
+use std::ops::{Bound, Index, IndexMut, Range, RangeBounds};
+use std::ptr::NonNull;
+
+use crate::array::{Array, ArrayMut, DisjointIndexMut, HasLength};
+
+/// Resolve a range argument against an array of length `len`.
+///
+/// Panics if the range is decreasing or extends past `len`.
+pub(crate) fn to_range<R>(range: &R, len: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end + 1,
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => len,
+    };
+    if start > end {
+        panic!("range start {} is greater than range end {}", start, end);
+    }
+    if end > len {
+        panic!("range end {} out of bounds for length {}", end, len);
+    }
+    start..end
+}
+
+/// A view of a contiguous range of an array.
+pub struct ArraySlice<'a, Arr: ?Sized> {
+    array: &'a Arr,
+    offset: usize,
+    len: usize,
+}
+
+impl<'a, Arr> ArraySlice<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    /// Construct a view of `array` covering `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn new<R>(array: &'a Arr, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = to_range(&range, array.len());
+        ArraySlice {
+            array,
+            offset: range.start,
+            len: range.end - range.start,
+        }
+    }
+
+    /// Get the range of the underlying array this view covers.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+impl<'a, Arr: ?Sized> Clone for ArraySlice<'a, Arr> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, Arr: ?Sized> Copy for ArraySlice<'a, Arr> {}
+
+impl<'a, Arr: ?Sized> HasLength for ArraySlice<'a, Arr> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, Arr> Index<usize> for ArraySlice<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    type Output = <Arr as Index<usize>>::Output;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        if index >= self.len {
+            panic!(
+                "ArraySlice::index: index {} out of bounds for length {}",
+                index, self.len
+            );
+        }
+        &self.array[self.offset + index]
+    }
+}
+
+impl<'a, Arr> Array for ArraySlice<'a, Arr>
+where
+    Arr: Array + ?Sized,
+{
+    unsafe fn get_unchecked(&self, index: usize) -> &Self::Output {
+        self.array.get_unchecked(self.offset + index)
+    }
+}
+
+/// References to `N` elements at one end of an array, and a view of the rest
+/// of it, as returned by `Array::split_first_chunk` and
+/// `Array::split_last_chunk`.
+pub type ChunkSplit<'a, Arr, const N: usize> =
+    ([&'a <Arr as Index<usize>>::Output; N], ArraySlice<'a, Arr>);
+
+/// A mutable view of the elements before an element of an array, a mutable
+/// reference to that element, and a mutable view of the elements after it, as
+/// returned by `ArrayMut::select_nth_unstable`.
+pub type NthSplit<'a, Arr> = (
+    ArraySliceMut<'a, Arr>,
+    &'a mut <Arr as Index<usize>>::Output,
+    ArraySliceMut<'a, Arr>,
+);
+
+/// Split the range `0..len` into `count` contiguous ranges whose lengths
+/// differ by at most one.
+pub(crate) fn split_ranges(len: usize, count: usize) -> impl Iterator<Item = Range<usize>> {
+    let size = len / count;
+    let remainder = len % count;
+    (0..count).map(move |index| {
+        let start = index * size + index.min(remainder);
+        let end = start + size + (index < remainder) as usize;
+        start..end
+    })
+}
+
+/// A mutable view of a contiguous range of an array.
+///
+/// Mutable views of disjoint ranges of the same array can exist at the same
+/// time, as handed out by `ArrayMut::split_n_mut`, if the array implements
+/// `DisjointIndexMut`. Views of such an array may be sent to other threads
+/// if it's also `Send` and `Sync`, in which case
+/// `ArrayMut::get_unchecked_ptr_mut` may be called on the array concurrently
+/// for different indexes. A view never creates a mutable reference to the
+/// whole array.
+pub struct ArraySliceMut<'a, Arr: ?Sized> {
+    array: NonNull<Arr>,
+    offset: usize,
+    len: usize,
+    lifetime: PhantomData<&'a mut Arr>,
+}
+
+impl<'a, Arr> ArraySliceMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    /// Construct a mutable view of `array` covering `range`.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn new<R>(array: &'a mut Arr, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = to_range(&range, array.len());
+        unsafe { Self::from_raw(array.into(), range) }
+    }
+
+    /// Construct a mutable view of `range` in the array behind `array`.
+    ///
+    /// The caller must ensure the range is in bounds, that the array outlives
+    /// `'a`, and that no other live reference into the array overlaps the
+    /// range.
+    pub(crate) unsafe fn from_raw(array: NonNull<Arr>, range: Range<usize>) -> Self {
+        ArraySliceMut {
+            array,
+            offset: range.start,
+            len: range.end - range.start,
+            lifetime: PhantomData,
+        }
+    }
+
+    /// Get the range of the underlying array this view covers.
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+
+    /// Split the view into views of `0..mid` and `mid..len`.
+    ///
+    /// Panics if `mid` is greater than the view's length.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn split_at(self, mid: usize) -> (Self, Self) {
+        if mid > self.len {
+            panic!(
+                "ArraySliceMut::split_at: index {} out of bounds for length {}",
+                mid, self.len
+            );
+        }
+        let end = self.offset + self.len;
+        let mid = self.offset + mid;
+        // Both halves are in bounds, don't overlap, and take over this view's
+        // borrow of the array.
+        unsafe {
+            (
+                Self::from_raw(self.array, self.offset..mid),
+                Self::from_raw(self.array, mid..end),
+            )
+        }
+    }
+}
+
+unsafe impl<'a, Arr> Send for ArraySliceMut<'a, Arr> where
+    Arr: DisjointIndexMut + Send + Sync + ?Sized
+{
+}
+
+unsafe impl<'a, Arr> Sync for ArraySliceMut<'a, Arr> where Arr: Sync + ?Sized {}
+
+impl<'a, Arr: ?Sized> HasLength for ArraySliceMut<'a, Arr> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<'a, Arr> Index<usize> for ArraySliceMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    type Output = <Arr as Index<usize>>::Output;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        if index >= self.len {
+            panic!(
+                "ArraySliceMut::index: index {} out of bounds for length {}",
+                index, self.len
+            );
+        }
+        unsafe { &self.array.as_ref()[self.offset + index] }
+    }
+}
+
+impl<'a, Arr> IndexMut<usize> for ArraySliceMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        if index >= self.len {
+            panic!(
+                "ArraySliceMut::index_mut: index {} out of bounds for length {}",
+                index, self.len
+            );
+        }
+        unsafe { &mut *Arr::get_unchecked_ptr_mut(self.array.as_ptr(), self.offset + index) }
+    }
+}
+
+impl<'a, Arr> Array for ArraySliceMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    unsafe fn get_unchecked(&self, index: usize) -> &Self::Output {
+        self.array.as_ref().get_unchecked(self.offset + index)
+    }
+}
+
+impl<'a, Arr> ArrayMut for ArraySliceMut<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+{
+    unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut *Arr::get_unchecked_ptr_mut(self.array.as_ptr(), self.offset + index)
+    }
+
+    unsafe fn get_unchecked_ptr_mut(this: *mut Self, index: usize) -> *mut Self::Output {
+        // Read the fields through the pointer, so that views of a slice don't
+        // create references to it either.
+        Arr::get_unchecked_ptr_mut((*this).array.as_ptr(), (*this).offset + index)
+    }
+}
+
+unsafe impl<'a, Arr> DisjointIndexMut for ArraySliceMut<'a, Arr> where Arr: DisjointIndexMut + ?Sized
+{}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn slices() {
+        let mut vec: VecDeque<_> = (0..10).collect();
+        let slice = vec.slice(2..5);
+        assert_eq!(3, slice.len());
+        assert_eq!(Some(&2), slice.first());
+        assert_eq!(Some(&4), slice.last());
+        assert_eq!(None, slice.get(3));
+        assert_eq!(7..10, vec.slice(7..).range());
+        let mut slice = vec.slice_mut(..=3);
+        slice.sort_unstable_by(|l, r| r.cmp(l));
+        assert!(vec.starts_with(&[3, 2, 1, 0, 4]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let vec: VecDeque<_> = (0..10).collect();
+        vec.slice(5..11);
+    }
+
+    #[test]
+    fn split_n() {
+        let mut vec: VecDeque<_> = (0..10).collect();
+        let ranges: Vec<_> = vec.split_n(3).iter().map(|view| view.range()).collect();
+        assert_eq!(vec![0..4, 4..7, 7..10], ranges);
+        assert_eq!(5, vec.split_n(5).len());
+        assert_eq!(
+            vec![0..1, 1..1],
+            vec.slice(..1)
+                .split_n(2)
+                .iter()
+                .map(|view| view.range())
+                .collect::<Vec<_>>()
+        );
+        let mut views = vec.split_n_mut(4);
+        for view in views.iter_mut() {
+            let first = view[0];
+            view.fill_with_index(|_| first);
+        }
+        assert_eq!(vec![0, 0, 0, 3, 3, 3, 6, 6, 8, 8], Vec::from(vec));
+    }
+}
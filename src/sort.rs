@@ -2,103 +2,990 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::array::ArrayMut;
-use core::{cmp::Ordering, ops::Index};
+use crate::array::{Array, ArrayMut};
+use crate::slice::ArraySliceMut;
+use core::{cmp::Ordering, mem::ManuallyDrop, ops::Index};
 use rand_core::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256Plus;
 
-fn gen_range<R: RngCore>(rng: &mut R, min: usize, max: usize) -> usize {
+pub(crate) fn gen_range<R: RngCore>(rng: &mut R, min: usize, max: usize) -> usize {
     let range = max - min;
     min + (rng.next_u64() as usize % range)
 }
 
-// Adapted from the Java version at
+/// A strategy for choosing the pivot element when partitioning an array
+/// during a quicksort.
+///
+/// See `ArrayMut::sort_unstable_with`.
+pub trait PivotStrategy {
+    /// Choose the index of a pivot element in the inclusive range
+    /// `left..=right` of `array`.
+    ///
+    /// Sorting panics if the index returned is outside that range.
+    fn choose_pivot<Arr, F>(
+        &mut self,
+        array: &Arr,
+        left: usize,
+        right: usize,
+        cmp: &mut F,
+    ) -> usize
+    where
+        Arr: Array + ?Sized,
+        F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering;
+}
+
+/// Choose a pivot at random.
+///
+/// This is the default strategy, and makes quadratic behaviour very unlikely
+/// for any input. The random number generator is seeded with a fixed value,
+/// so sorting is still deterministic.
+#[derive(Clone, Debug)]
+pub struct RandomPivot {
+    rng: Xoshiro256Plus,
+}
+
+impl RandomPivot {
+    /// Construct a random pivot strategy with the given seed.
+    pub fn new(seed: u64) -> Self {
+        RandomPivot {
+            rng: Xoshiro256Plus::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for RandomPivot {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl PivotStrategy for RandomPivot {
+    fn choose_pivot<Arr, F>(
+        &mut self,
+        _array: &Arr,
+        left: usize,
+        right: usize,
+        _cmp: &mut F,
+    ) -> usize
+    where
+        Arr: Array + ?Sized,
+        F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    {
+        gen_range(&mut self.rng, left, right + 1)
+    }
+}
+
+/// Always choose the first element as the pivot.
+///
+/// This is the cheapest strategy, and works well for random data, but it
+/// degrades to quadratic time on sorted or nearly sorted input.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstPivot;
+
+impl PivotStrategy for FirstPivot {
+    fn choose_pivot<Arr, F>(
+        &mut self,
+        _array: &Arr,
+        left: usize,
+        _right: usize,
+        _cmp: &mut F,
+    ) -> usize
+    where
+        Arr: Array + ?Sized,
+        F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    {
+        left
+    }
+}
+
+/// Choose the median of the first, middle and last elements as the pivot.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MedianOfThree;
+
+impl PivotStrategy for MedianOfThree {
+    fn choose_pivot<Arr, F>(&mut self, array: &Arr, left: usize, right: usize, cmp: &mut F) -> usize
+    where
+        Arr: Array + ?Sized,
+        F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    {
+        median_of_three(array, left, left + (right - left) / 2, right, cmp)
+    }
+}
+
+/// Choose the median of three medians of three as the pivot, also known as
+/// Tukey's ninther.
+///
+/// This gives a better estimate of the true median than `MedianOfThree` for
+/// large ranges, at the cost of a few more comparisons. Small ranges fall back
+/// to `MedianOfThree`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ninther;
+
+impl PivotStrategy for Ninther {
+    fn choose_pivot<Arr, F>(&mut self, array: &Arr, left: usize, right: usize, cmp: &mut F) -> usize
+    where
+        Arr: Array + ?Sized,
+        F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    {
+        let len = right - left + 1;
+        if len < 40 {
+            return MedianOfThree.choose_pivot(array, left, right, cmp);
+        }
+        let step = len / 8;
+        let mid = left + len / 2;
+        let a = median_of_three(array, left, left + step, left + 2 * step, cmp);
+        let b = median_of_three(array, mid - step, mid, mid + step, cmp);
+        let c = median_of_three(array, right - 2 * step, right - step, right, cmp);
+        median_of_three(array, a, b, c, cmp)
+    }
+}
+
+fn median_of_three<Arr, F>(array: &Arr, a: usize, b: usize, c: usize, cmp: &mut F) -> usize
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    let ab = cmp(&array[a], &array[b]) == Ordering::Less;
+    let bc = cmp(&array[b], &array[c]) == Ordering::Less;
+    if ab == bc {
+        b
+    } else if ab == (cmp(&array[a], &array[c]) == Ordering::Less) {
+        c
+    } else {
+        a
+    }
+}
+
+/// Ranges shorter than this are sorted with insertion sort rather than
+/// partitioned further.
+pub(crate) const INSERTION_SORT_THRESHOLD: usize = 24;
+
+/// The number of levels of partitioning the introsort allows for a range of
+/// `len` elements before it gives up on quicksort, which is twice the base 2
+/// logarithm of `len`.
+fn depth_limit(len: usize) -> u32 {
+    2 * (usize::BITS - len.leading_zeros())
+}
+
+// An introsort built around the quicksort adapted from the Java version at
 //    http://www.cs.princeton.edu/~rs/talks/QuicksortIsOptimal.pdf
-// with semi-randomised pivot points.
-// Should be O(n) to O(n log n)
-fn do_quicksort<Arr, F, R>(array: &mut Arr, left: usize, right: usize, cmp: &mut F, rng: &mut R)
+// with pluggable pivot selection. Short ranges are insertion sorted, and
+// ranges which have been partitioned too many times, which means the pivots
+// are being chosen badly, are heapsorted, so the whole sort is O(n log n)
+// in the worst case.
+//
+// Only the smaller side of each partition is sorted recursively, while the
+// larger side is sorted by looping, so the recursion is never more than
+// log2(n) calls deep, however badly the pivots are chosen.
+fn do_quicksort<Arr, F, P>(
+    array: &mut Arr,
+    mut left: usize,
+    mut right: usize,
+    cmp: &mut F,
+    pivot: &mut P,
+    threshold: usize,
+    mut depth: u32,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    P: PivotStrategy,
+{
+    while left < right {
+        if right - left + 1 < threshold {
+            insertion_sort(array, left, right, cmp);
+            return;
+        }
+        if depth == 0 {
+            crate::heap::heapsort(&mut ArraySliceMut::new(array, left..=right), cmp);
+            return;
+        }
+        depth -= 1;
+
+        let (r1, l1) = partition(array, left, right, cmp, pivot);
+        // The lesser elements are in `left..=r1` and the greater ones in
+        // `l1..=right`, either of which may be empty.
+        let lesser = (r1 + 1 - left as isize).max(0) as usize;
+        let greater = (right as isize + 1 - l1).max(0) as usize;
+        if lesser < greater {
+            if lesser > 1 {
+                do_quicksort(array, left, r1 as usize, cmp, pivot, threshold, depth);
+            }
+            left = l1 as usize;
+        } else {
+            if greater > 1 {
+                do_quicksort(array, l1 as usize, right, cmp, pivot, threshold, depth);
+            }
+            if lesser == 0 {
+                return;
+            }
+            right = r1 as usize;
+        }
+    }
+}
+
+/// The length of the runs `merge_sort` sorts with insertion sort before it
+/// starts merging.
+const MERGE_SORT_RUN: usize = 16;
+
+/// Stably sort `array` with a bottom up merge sort.
+///
+/// Runs of `MERGE_SORT_RUN` elements are insertion sorted first, and then
+/// merged pairwise into ever longer runs, reusing a single scratch buffer
+/// for every merge.
+pub(crate) fn merge_sort<Arr, F>(array: &mut Arr, mut cmp: F)
 where
     Arr: ArrayMut + ?Sized,
     <Arr as Index<usize>>::Output: Sized,
     F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
-    R: RngCore,
 {
-    if right <= left {
+    #[cfg(all(feature = "validate-ordering", debug_assertions))]
+    let mut cmp = crate::validate::comparator(&mut cmp);
+    let len = array.len();
+    if len < 2 {
         return;
     }
+    for start in (0..len).step_by(MERGE_SORT_RUN) {
+        insertion_sort(array, start, len.min(start + MERGE_SORT_RUN) - 1, &mut cmp);
+    }
+    let mut buffer = Vec::new();
+    let mut width = MERGE_SORT_RUN;
+    while width < len {
+        for start in (0..len - width).step_by(width * 2) {
+            let mid = start + width;
+            let end = len.min(mid + width);
+            // Runs which are already in order relative to each other don't
+            // need merging.
+            if cmp(&array[mid - 1], &array[mid]) == Ordering::Greater {
+                crate::merge::merge_with_buffer(array, start, mid, end, &mut cmp, &mut buffer);
+            }
+        }
+        width *= 2;
+    }
+}
+
+/// Sort the inclusive range `left..=right` with insertion sort, which is
+/// faster than partitioning for very short ranges.
+fn insertion_sort<Arr, F>(array: &mut Arr, left: usize, right: usize, cmp: &mut F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    if right >= array.len() {
+        panic!(
+            "insertion_sort: range end {} out of bounds for length {}",
+            right,
+            array.len()
+        );
+    }
+    for start in left + 1..=right {
+        let mut index = start;
+        // Every index stays within `left..=right`, which is in bounds.
+        unsafe {
+            while index > left
+                && cmp(array.get_unchecked(index), array.get_unchecked(index - 1)) == Ordering::Less
+            {
+                array.swap_unchecked(index, index - 1);
+                index -= 1;
+            }
+        }
+    }
+}
 
+/// Partition the inclusive range `left..=right` around a pivot into three
+/// parts: elements less than the pivot, elements equal to it, and elements
+/// greater than it. The range must hold at least two elements.
+///
+/// Returns `(r1, l1)` such that `left..=r1` holds the lesser elements and
+/// `l1..=right` the greater ones, with the equal elements in between. `r1`
+/// is less than `left` if there are no lesser elements, and `l1` is greater
+/// than `right` if there are no greater elements.
+fn partition<Arr, F, P>(
+    array: &mut Arr,
+    left: usize,
+    right: usize,
+    cmp: &mut F,
+    pivot: &mut P,
+) -> (isize, isize)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    P: PivotStrategy,
+{
+    if right >= array.len() {
+        panic!(
+            "partition: range end {} out of bounds for length {}",
+            right,
+            array.len()
+        );
+    }
     let l = left as isize;
     let r = right as isize;
-    let p = gen_range(rng, left, right + 1) as isize;
-    let mut l1 = l;
+    let p = pivot.choose_pivot(array, left, right, cmp);
+    if p < left || p > right {
+        panic!(
+            "partition: pivot index {} out of range {}..={}",
+            p, left, right
+        );
+    }
+    let mut l1 = l - 1;
     let mut r1 = r;
     let mut l2 = l - 1;
     let mut r2 = r;
 
-    array.swap(r as usize, p as usize);
-    loop {
-        while l1 != r && array.map_pair(l1 as usize, r as usize, |a, b| cmp(a, b)) == Ordering::Less
-        {
+    // Every index used below stays within `left..=right`, which we've just
+    // checked is in bounds, so we can skip the bounds checks in the inner
+    // loops. The pivot lives at index `r` for the duration of the
+    // partitioning.
+    unsafe {
+        array.swap_unchecked(right, p);
+        loop {
             l1 += 1;
+            while l1 != r
+                && cmp(array.get_unchecked(l1 as usize), array.get_unchecked(right))
+                    == Ordering::Less
+            {
+                l1 += 1;
+            }
+
+            r1 -= 1;
+            while cmp(array.get_unchecked(right), array.get_unchecked(r1 as usize))
+                == Ordering::Less
+            {
+                if r1 == l {
+                    break;
+                }
+                r1 -= 1;
+            }
+            if l1 >= r1 {
+                break;
+            }
+            array.swap_unchecked(l1 as usize, r1 as usize);
+            if cmp(array.get_unchecked(l1 as usize), array.get_unchecked(right)) == Ordering::Equal
+            {
+                l2 += 1;
+                array.swap_unchecked(l2 as usize, l1 as usize);
+            }
+            if cmp(array.get_unchecked(right), array.get_unchecked(r1 as usize)) == Ordering::Equal
+            {
+                r2 -= 1;
+                array.swap_unchecked(r1 as usize, r2 as usize);
+            }
         }
+        array.swap_unchecked(l1 as usize, right);
+        let middle = l1 as usize;
 
-        r1 -= 1;
-        while r1 != r && array.map_pair(r as usize, r1 as usize, |a, b| cmp(a, b)) == Ordering::Less
+        // Move the elements equal to the pivot from the ends into the middle.
+        // Where the scans crossed, elements equal to the pivot may already
+        // sit just below it, or have been swapped out to `right` above, so
+        // those join the ones at the ends.
+        r1 = l1 - 1;
+        while r1 > l2
+            && cmp(
+                array.get_unchecked(r1 as usize),
+                array.get_unchecked(middle),
+            ) == Ordering::Equal
+        {
+            r1 -= 1;
+        }
+        l1 += 1;
+        let mut k = l;
+        while k <= l2 {
+            array.swap_unchecked(k as usize, r1 as usize);
+            r1 -= 1;
+            k += 1;
+        }
+        // If the left scan stopped inside the equal elements at the right
+        // end, the pivot now sits among them and only the ones above it move.
+        r2 = r2.max(l1);
+        k = if l1 <= r
+            && cmp(array.get_unchecked(right), array.get_unchecked(middle)) == Ordering::Equal
         {
-            if r1 == l {
+            r
+        } else {
+            r - 1
+        };
+        while k >= r2 {
+            array.swap_unchecked(l1 as usize, k as usize);
+            k -= 1;
+            l1 += 1;
+        }
+    }
+
+    (r1, l1)
+}
+
+/// Holds the element being carried around a cycle by `cycle_sort`, and
+/// writes it into the slot at the start of the cycle when dropped, restoring
+/// the array's original elements if the comparator panics.
+struct CycleGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    array: &'a mut Arr,
+    start: usize,
+    item: Option<<Arr as Index<usize>>::Output>,
+}
+
+impl<'a, Arr> Drop for CycleGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    fn drop(&mut self) {
+        if let Some(item) = self.item.take() {
+            self.array[self.start] = item;
+        }
+    }
+}
+
+/// Find the index in the range `start..` where `item` belongs, skipping past
+/// any elements equal to it.
+fn cycle_position<Arr, F>(
+    array: &Arr,
+    start: usize,
+    item: &<Arr as Index<usize>>::Output,
+    cmp: &mut F,
+) -> usize
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    let mut pos = start;
+    for index in start + 1..array.len() {
+        if cmp(&array[index], item) == Ordering::Less {
+            pos += 1;
+        }
+    }
+    pos
+}
+
+/// Sort an array with cycle sort, which writes each element that isn't
+/// already in place exactly once, at the cost of O(n²) comparisons.
+pub(crate) fn cycle_sort<Arr, F>(array: &mut Arr, mut cmp: F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Clone,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    #[cfg(all(feature = "validate-ordering", debug_assertions))]
+    let mut cmp = crate::validate::comparator(&mut cmp);
+    for start in 0..array.len().saturating_sub(1) {
+        let mut pos = cycle_position(array, start, &array[start], &mut cmp);
+        if pos == start {
+            continue;
+        }
+        // The slot at `start` keeps a stale copy of its element until the
+        // cycle closes and the guard writes the final element into it.
+        let item = array[start].clone();
+        let mut guard = CycleGuard {
+            array: &mut *array,
+            start,
+            item: Some(item),
+        };
+        loop {
+            let item = guard.item.as_mut().unwrap();
+            while cmp(item, &guard.array[pos]) == Ordering::Equal {
+                pos += 1;
+            }
+            if pos == start {
                 break;
             }
-            r1 -= 1;
+            std::mem::swap(item, &mut guard.array[pos]);
+            pos = cycle_position(guard.array, start, guard.item.as_ref().unwrap(), &mut cmp);
         }
-        if l1 >= r1 {
-            break;
+    }
+}
+
+/// Rearrange the inclusive range `left..=right` so that each of the indexes
+/// in `ranks`, which must be sorted and within the range, holds the element
+/// that would be there if the range were sorted, with lesser elements before
+/// it and greater elements after it.
+pub(crate) fn multiselect<Arr, F, P>(
+    array: &mut Arr,
+    left: usize,
+    right: usize,
+    ranks: &[usize],
+    cmp: &mut F,
+    pivot: &mut P,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    P: PivotStrategy,
+{
+    if ranks.is_empty() || right <= left {
+        return;
+    }
+    let (r1, l1) = partition(array, left, right, cmp, pivot);
+    // Ranks landing among the elements equal to the pivot are already done.
+    let lesser = ranks.partition_point(|&rank| (rank as isize) <= r1);
+    let greater = ranks.partition_point(|&rank| (rank as isize) < l1);
+    if lesser > 0 {
+        multiselect(array, left, r1 as usize, &ranks[..lesser], cmp, pivot);
+    }
+    if greater < ranks.len() {
+        multiselect(array, l1 as usize, right, &ranks[greater..], cmp, pivot);
+    }
+}
+
+pub(crate) fn quicksort<Arr, F>(array: &mut Arr, left: usize, right: usize, cmp: F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    quicksort_with(array, left, right, cmp, &mut RandomPivot::default());
+}
+
+pub(crate) fn quicksort_with<Arr, F, P>(
+    array: &mut Arr,
+    left: usize,
+    right: usize,
+    cmp: F,
+    pivot: &mut P,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    P: PivotStrategy,
+{
+    quicksort_with_threshold(array, left, right, cmp, pivot, INSERTION_SORT_THRESHOLD);
+}
+
+/// Quicksort the inclusive range `left..=right`, switching to insertion
+/// sort for ranges shorter than `threshold`.
+pub(crate) fn quicksort_with_threshold<Arr, F, P>(
+    array: &mut Arr,
+    left: usize,
+    right: usize,
+    mut cmp: F,
+    pivot: &mut P,
+    threshold: usize,
+) where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    P: PivotStrategy,
+{
+    #[cfg(all(feature = "validate-ordering", debug_assertions))]
+    let mut cmp = {
+        let mut rng = Xoshiro256Plus::seed_from_u64(0);
+        crate::validate::check_transitivity(array, left..right + 1, &mut cmp, &mut rng);
+        crate::validate::comparator(cmp)
+    };
+    if presorted(array, left, right, &mut cmp) {
+        return;
+    }
+    let depth = depth_limit(right - left + 1);
+    do_quicksort(array, left, right, &mut cmp, pivot, threshold, depth);
+}
+
+/// Check whether the inclusive range `left..=right` is already sorted, or
+/// sorted in reverse, in which case it's reversed into order. Returns `true`
+/// if the range is sorted after this.
+///
+/// This costs at most one comparison per element, and for unsorted input
+/// it usually gives up after only a few.
+fn presorted<Arr, F>(array: &mut Arr, left: usize, right: usize, cmp: &mut F) -> bool
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    if right >= array.len() {
+        panic!(
+            "presorted: range end {} out of bounds for length {}",
+            right,
+            array.len()
+        );
+    }
+    let mut ascending = true;
+    let mut descending = true;
+    for index in left..right {
+        // Both indexes are within `left..=right`, which is in bounds.
+        let ordering = unsafe { cmp(array.get_unchecked(index), array.get_unchecked(index + 1)) };
+        match ordering {
+            Ordering::Less => descending = false,
+            Ordering::Greater => ascending = false,
+            Ordering::Equal => {}
         }
-        array.swap(l1 as usize, r1 as usize);
-        if l1 != r && array.map_pair(l1 as usize, r as usize, |a, b| cmp(a, b)) == Ordering::Equal {
-            l2 += 1;
-            array.swap(l2 as usize, l1 as usize);
+        if !ascending && !descending {
+            return false;
         }
-        if r1 != r && array.map_pair(r as usize, r1 as usize, |a, b| cmp(a, b)) == Ordering::Equal {
-            r2 -= 1;
-            array.swap(r1 as usize, r2 as usize);
+    }
+    if !ascending {
+        crate::merge::reverse(array, left, right + 1);
+    }
+    true
+}
+
+/// The algorithm used by `ArrayMut::sort_with_options`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortAlgorithm {
+    /// Sort the elements in place with quicksort, as `sort_unstable_by`
+    /// does.
+    Quicksort,
+    /// Sort a list of indexes first and then move each element directly to
+    /// its final position, as `sort_unstable_indirect_by` does.
+    Indirect,
+}
+
+/// How quicksort pivots are chosen by `ArrayMut::sort_with_options`.
+///
+/// Each variant corresponds to one of the `PivotStrategy` implementations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PivotChoice {
+    /// Choose pivots at random, using a generator seeded with the given
+    /// value, as `RandomPivot` does.
+    Random(u64),
+    /// Always choose the first element, as `FirstPivot` does.
+    First,
+    /// Choose the median of the first, middle and last elements, as
+    /// `MedianOfThree` does.
+    MedianOfThree,
+    /// Choose Tukey's ninther, as `Ninther` does.
+    Ninther,
+}
+
+/// Options controlling how `ArrayMut::sort_with_options` sorts an array.
+///
+/// The default options sort exactly like `ArrayMut::sort_unstable_by`. Use
+/// the builder methods to tune them for a particular container: for
+/// instance, arrays which are expensive to write to might prefer
+/// `SortAlgorithm::Indirect`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use array_ops::*;
+/// # use std::collections::VecDeque;
+/// let mut vec: VecDeque<_> = vec![5, 3, 1, 4, 2].into();
+/// let options = SortOptions::new()
+///     .pivot(PivotChoice::MedianOfThree)
+///     .small_array_threshold(16);
+/// vec.sort_with_options(options, Ord::cmp);
+/// assert_eq!(vec, vec![1, 2, 3, 4, 5]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortOptions {
+    algorithm: SortAlgorithm,
+    pivot: PivotChoice,
+    small_array_threshold: usize,
+    stable: bool,
+}
+
+impl SortOptions {
+    /// Construct the default sort options.
+    pub fn new() -> Self {
+        SortOptions {
+            algorithm: SortAlgorithm::Quicksort,
+            pivot: PivotChoice::Random(0),
+            small_array_threshold: INSERTION_SORT_THRESHOLD,
+            stable: false,
         }
     }
-    array.swap(l1 as usize, r as usize);
 
-    r1 = l1 - 1;
-    l1 += 1;
-    let mut k = l;
-    while k < l2 {
-        array.swap(k as usize, r1 as usize);
-        r1 -= 1;
-        k += 1;
+    /// Choose the sorting algorithm.
+    pub fn algorithm(mut self, algorithm: SortAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
     }
-    k = r - 1;
-    while k > r2 {
-        array.swap(l1 as usize, k as usize);
-        k -= 1;
-        l1 += 1;
+
+    /// Choose how quicksort pivots are chosen.
+    pub fn pivot(mut self, pivot: PivotChoice) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Sort ranges shorter than `threshold` with insertion sort rather than
+    /// partitioning them further. This only affects quicksort, and defaults
+    /// to 24.
+    pub fn small_array_threshold(mut self, threshold: usize) -> Self {
+        self.small_array_threshold = threshold;
+        self
+    }
+
+    /// Require the sort to be stable, so that equal elements keep their
+    /// original order. This overrides the choice of algorithm.
+    pub fn stable(mut self, stable: bool) -> Self {
+        self.stable = stable;
+        self
+    }
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sort `array` as described by `options`.
+pub(crate) fn sort_with_options<Arr, F>(array: &mut Arr, options: SortOptions, cmp: F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    if options.stable {
+        return sort_by_permutation(array, cmp, true);
+    }
+    if options.algorithm == SortAlgorithm::Indirect {
+        return sort_by_permutation(array, cmp, false);
+    }
+    if array.len() < 2 {
+        return;
+    }
+    let right = array.len() - 1;
+    let threshold = options.small_array_threshold;
+    match options.pivot {
+        PivotChoice::Random(seed) => {
+            quicksort_with_threshold(array, 0, right, cmp, &mut RandomPivot::new(seed), threshold)
+        }
+        PivotChoice::First => {
+            quicksort_with_threshold(array, 0, right, cmp, &mut FirstPivot, threshold)
+        }
+        PivotChoice::MedianOfThree => {
+            quicksort_with_threshold(array, 0, right, cmp, &mut MedianOfThree, threshold)
+        }
+        PivotChoice::Ninther => {
+            quicksort_with_threshold(array, 0, right, cmp, &mut Ninther, threshold)
+        }
+    }
+}
+
+/// A sort order made up of one or more keys, each sorted ascending or
+/// descending, with later keys breaking ties between earlier ones.
+///
+/// See `ArrayMut::sort_with_spec`.
+///
+/// # Examples
+///
+/// ```
+/// # use array_ops::*;
+/// # use std::collections::VecDeque;
+/// let mut people: VecDeque<_> = vec![("Ann", 32), ("Bob", 25), ("Cat", 32)].into();
+/// people.sort_with_spec(SortSpec::by_key(|p: &(&str, u32)| p.1).desc().then_by_key(|p| p.0));
+/// assert_eq!(people, vec![("Ann", 32), ("Cat", 32), ("Bob", 25)]);
+/// ```
+pub struct SortSpec<'a, A: ?Sized> {
+    keys: Vec<SortKey<'a, A>>,
+}
+
+type Comparator<'a, A> = Box<dyn Fn(&A, &A) -> Ordering + 'a>;
+
+/// A single key of a `SortSpec`.
+struct SortKey<'a, A: ?Sized> {
+    compare: Comparator<'a, A>,
+    descending: bool,
+}
+
+impl<'a, A: ?Sized> SortSpec<'a, A> {
+    /// Construct a sort order by the key `extract` returns for each element.
+    pub fn by_key<K, F>(extract: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&A) -> K + 'a,
+    {
+        Self::by(move |left, right| extract(left).cmp(&extract(right)))
+    }
+
+    /// Construct a sort order by a comparator function.
+    pub fn by<F>(compare: F) -> Self
+    where
+        F: Fn(&A, &A) -> Ordering + 'a,
+    {
+        SortSpec {
+            keys: vec![SortKey {
+                compare: Box::new(compare),
+                descending: false,
+            }],
+        }
     }
 
-    if r1 >= 0 {
-        do_quicksort(array, left, r1 as usize, cmp, rng);
+    /// Add a key to break ties between elements which are equal by the
+    /// previous keys.
+    pub fn then_by_key<K, F>(self, extract: F) -> Self
+    where
+        K: Ord,
+        F: Fn(&A) -> K + 'a,
+    {
+        self.then_by(move |left, right| extract(left).cmp(&extract(right)))
+    }
+
+    /// Add a comparator function to break ties between elements which are
+    /// equal by the previous keys.
+    pub fn then_by<F>(mut self, compare: F) -> Self
+    where
+        F: Fn(&A, &A) -> Ordering + 'a,
+    {
+        self.keys.push(SortKey {
+            compare: Box::new(compare),
+            descending: false,
+        });
+        self
+    }
+
+    /// Sort by the most recently added key in ascending order. This is the
+    /// default.
+    pub fn asc(mut self) -> Self {
+        if let Some(key) = self.keys.last_mut() {
+            key.descending = false;
+        }
+        self
+    }
+
+    /// Sort by the most recently added key in descending order.
+    pub fn desc(mut self) -> Self {
+        if let Some(key) = self.keys.last_mut() {
+            key.descending = true;
+        }
+        self
+    }
+
+    /// Compare two elements according to this sort order.
+    pub fn compare(&self, left: &A, right: &A) -> Ordering {
+        for key in &self.keys {
+            let ordering = if key.descending {
+                (key.compare)(right, left)
+            } else {
+                (key.compare)(left, right)
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        Ordering::Equal
     }
-    do_quicksort(array, l1 as usize, right, cmp, rng);
 }
 
-pub(crate) fn quicksort<Arr, F>(array: &mut Arr, left: usize, right: usize, mut cmp: F)
+/// Sort an array by sorting a permutation of its indexes and then applying
+/// it to the array, so that each element is moved at most once.
+///
+/// The sort is stable if `stable` is true.
+pub(crate) fn sort_by_permutation<Arr, F>(array: &mut Arr, cmp: F, stable: bool)
 where
     Arr: ArrayMut + ?Sized,
     <Arr as Index<usize>>::Output: Sized,
     F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
 {
-    let mut rng = rand_xoshiro::Xoshiro256Plus::seed_from_u64(0);
-    do_quicksort(array, left, right, &mut cmp, &mut rng);
+    let mut permutation = sorted_indexes(array, cmp, stable);
+    apply_permutation(array, &mut permutation);
+}
+
+/// Sort `array` by the key `extract` returns for each element, calling
+/// `extract` only once per element.
+pub(crate) fn sort_by_cached_key<Arr, F, K>(array: &mut Arr, mut extract: F)
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output) -> K,
+    K: Ord,
+{
+    let mut keys: Vec<(K, usize)> = (0..array.len())
+        .map(|index| (extract(&array[index]), index))
+        .collect();
+    keys.sort_unstable_by(|left, right| left.0.cmp(&right.0));
+    let mut permutation: Vec<usize> = keys.into_iter().map(|(_, index)| index).collect();
+    apply_permutation(array, &mut permutation);
+}
+
+/// Get the indexes of the elements of `array` in the order the elements
+/// would have if the array were sorted, without touching the array itself.
+pub(crate) fn sorted_indexes<Arr, F>(array: &Arr, mut cmp: F, stable: bool) -> Vec<usize>
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+{
+    #[cfg(all(feature = "validate-ordering", debug_assertions))]
+    let mut cmp = crate::validate::comparator(&mut cmp);
+    let mut indexes: Vec<usize> = (0..array.len()).collect();
+    if stable {
+        indexes.sort_by(|&left, &right| cmp(&array[left], &array[right]));
+    } else {
+        indexes.sort_unstable_by(|&left, &right| cmp(&array[left], &array[right]));
+    }
+    indexes
+}
+
+/// Holds the element carried around a cycle by `apply_permutation`, and
+/// moves it into the hole the cycle has reached when dropped, so that the
+/// array still holds each of its elements exactly once if `index_mut`
+/// panics part way through the cycle.
+struct PermutationGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    array: &'a mut Arr,
+    hole: usize,
+    held: ManuallyDrop<<Arr as Index<usize>>::Output>,
+}
+
+impl<'a, Arr> Drop for PermutationGuard<'a, Arr>
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    fn drop(&mut self) {
+        unsafe {
+            let hole: *mut <Arr as Index<usize>>::Output = &mut self.array[self.hole];
+            std::ptr::write(hole, ManuallyDrop::take(&mut self.held));
+        }
+    }
+}
+
+/// Rearrange `array` so that each index `i` holds the element which was at
+/// `permutation[i]`, moving each element at most once. The permutation is
+/// reset to the identity as it's applied.
+fn apply_permutation<Arr>(array: &mut Arr, permutation: &mut [usize])
+where
+    Arr: ArrayMut + ?Sized,
+    <Arr as Index<usize>>::Output: Sized,
+{
+    for start in 0..permutation.len() {
+        if permutation[start] == start {
+            continue;
+        }
+        // Follow the cycle through `start`, moving each element back into
+        // the hole left by the one before it. Each pointer is used right
+        // after `index_mut` returns it: the moved element is read into a
+        // local before the hole's pointer is taken, and the guard fills the
+        // hole if an `index_mut` call panics, leaving the local's copy to be
+        // forgotten while the original stays in place.
+        unsafe {
+            let held: *mut <Arr as Index<usize>>::Output = &mut array[start];
+            let mut guard = PermutationGuard {
+                held: ManuallyDrop::new(std::ptr::read(held)),
+                array: &mut *array,
+                hole: start,
+            };
+            loop {
+                let source = permutation[guard.hole];
+                if source == start {
+                    break;
+                }
+                let value: *mut <Arr as Index<usize>>::Output = &mut guard.array[source];
+                let value = ManuallyDrop::new(std::ptr::read(value));
+                let hole: *mut <Arr as Index<usize>>::Output = &mut guard.array[guard.hole];
+                std::ptr::write(hole, ManuallyDrop::into_inner(value));
+                permutation[guard.hole] = guard.hole;
+                guard.hole = source;
+            }
+            permutation[guard.hole] = guard.hole;
+            let hole: *mut <Arr as Index<usize>>::Output = &mut guard.array[guard.hole];
+            std::ptr::write(hole, ManuallyDrop::take(&mut guard.held));
+            std::mem::forget(guard);
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::array::Array;
     use std::collections::VecDeque;
 
     #[test]
@@ -111,4 +998,394 @@ mod test {
         quicksort(&mut vec, 0, last, &Ord::cmp);
         assert!(vec.is_sorted());
     }
+
+    #[test]
+    fn test_quicksort_panic_safety() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: Vec<String> = std::iter::from_fn(move || Some(rng.next_u64()))
+            .map(|n| n.to_string())
+            .take(1024)
+            .collect();
+        for limit in &[0, 1, 10, 100, 1000, 5000] {
+            let mut vec: VecDeque<_> = original.iter().cloned().collect();
+            let mut count = 0;
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let last = vec.len() - 1;
+                quicksort(&mut vec, 0, last, |l: &String, r: &String| {
+                    count += 1;
+                    if count > *limit {
+                        panic!("comparator panic");
+                    }
+                    l.cmp(r)
+                });
+            }));
+            assert!(result.is_err());
+            let mut remaining: Vec<_> = vec.into_iter().collect();
+            let mut expected = original.clone();
+            remaining.sort();
+            expected.sort();
+            assert_eq!(expected, remaining);
+        }
+    }
+
+    #[test]
+    fn test_pivot_strategies() {
+        fn check<P: PivotStrategy>(mut pivot: P) {
+            let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+            for modulo in &[1, 2, 100, u64::MAX] {
+                let mut vec: VecDeque<_> = std::iter::from_fn(|| Some(rng.next_u64() % modulo))
+                    .take(1000)
+                    .collect();
+                let last = vec.len() - 1;
+                quicksort_with(&mut vec, 0, last, Ord::cmp, &mut pivot);
+                assert!(vec.is_sorted());
+            }
+        }
+        check(RandomPivot::new(1));
+        check(FirstPivot);
+        check(MedianOfThree);
+        check(Ninther);
+    }
+
+    #[test]
+    fn test_partition() {
+        // Every array of up to six elements drawn from three distinct values,
+        // partitioned over every subrange, so there are plenty of elements
+        // equal to the pivot at both ends of the range and where the scans
+        // cross.
+        fn check<P: PivotStrategy>(mut pivot: P) {
+            for len in 1..=6u32 {
+                for code in 0..3usize.pow(len) {
+                    let original: VecDeque<usize> =
+                        (0..len).map(|i| code / 3usize.pow(i) % 3).collect();
+                    for left in 0..original.len() {
+                        for right in left + 1..original.len() {
+                            let mut vec = original.clone();
+                            let (r1, l1) =
+                                partition(&mut vec, left, right, &mut Ord::cmp, &mut pivot);
+                            assert!(r1 + 1 >= left as isize && r1 < l1 - 1);
+                            assert!(l1 <= right as isize + 1);
+                            let (r1, l1) = ((r1 + 1) as usize, l1 as usize);
+                            let value = vec[r1];
+                            assert!((left..r1).all(|i| vec[i] < value));
+                            assert!((r1..l1).all(|i| vec[i] == value));
+                            assert!((l1..=right).all(|i| vec[i] > value));
+                            assert!((0..left).all(|i| vec[i] == original[i]));
+                            assert!((right + 1..vec.len()).all(|i| vec[i] == original[i]));
+                            let mut sorted: Vec<_> = vec.iter().cloned().collect();
+                            let mut expected: Vec<_> = original.iter().cloned().collect();
+                            sorted.sort();
+                            expected.sort();
+                            assert_eq!(expected, sorted);
+                        }
+                    }
+                }
+            }
+        }
+        check(RandomPivot::new(1));
+        check(FirstPivot);
+        check(MedianOfThree);
+    }
+
+    #[test]
+    fn test_presorted() {
+        let mut comparisons = 0;
+        let mut vec: VecDeque<_> = (0..1000).collect();
+        quicksort(&mut vec, 0, 999, |l: &i32, r: &i32| {
+            comparisons += 1;
+            l.cmp(r)
+        });
+        // The comparator validation makes extra comparisons of its own.
+        #[cfg(not(all(feature = "validate-ordering", debug_assertions)))]
+        assert_eq!(999, comparisons);
+        let mut vec: VecDeque<_> = (0..1000).rev().collect();
+        quicksort(&mut vec, 0, 999, Ord::cmp);
+        assert!(vec.is_sorted());
+        let mut vec: VecDeque<_> = vec![5; 1000].into();
+        quicksort(&mut vec, 0, 999, Ord::cmp);
+        assert!(vec.is_sorted());
+        let mut vec: VecDeque<_> = vec![3, 3, 2, 2, 1, 1].into();
+        quicksort(&mut vec, 0, 5, Ord::cmp);
+        assert_eq!(vec![1, 1, 2, 2, 3, 3], Vec::from(vec));
+    }
+
+    #[test]
+    fn test_multiselect() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: Vec<_> = std::iter::from_fn(|| Some(rng.next_u64() % 500))
+            .take(1000)
+            .collect();
+        let mut sorted = original.clone();
+        sorted.sort();
+        let ranks = [0, 250, 251, 500, 750, 999];
+        let mut vec: VecDeque<_> = original.into_iter().collect();
+        multiselect(
+            &mut vec,
+            0,
+            999,
+            &ranks,
+            &mut Ord::cmp,
+            &mut RandomPivot::default(),
+        );
+        for &rank in &ranks {
+            assert_eq!(sorted[rank], vec[rank]);
+            assert!((0..rank).all(|index| vec[index] <= vec[rank]));
+            assert!((rank..1000).all(|index| vec[index] >= vec[rank]));
+        }
+    }
+
+    #[test]
+    fn test_cycle_sort() {
+        use std::cell::Cell;
+        use std::ops::IndexMut;
+
+        struct Counting<'a>(Vec<u64>, &'a Cell<usize>);
+
+        impl<'a> crate::HasLength for Counting<'a> {
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        impl<'a> Index<usize> for Counting<'a> {
+            type Output = u64;
+            fn index(&self, index: usize) -> &u64 {
+                &self.0[index]
+            }
+        }
+
+        impl<'a> IndexMut<usize> for Counting<'a> {
+            fn index_mut(&mut self, index: usize) -> &mut u64 {
+                self.1.set(self.1.get() + 1);
+                &mut self.0[index]
+            }
+        }
+
+        impl<'a> Array for Counting<'a> {}
+        impl<'a> ArrayMut for Counting<'a> {}
+
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        for &modulus in &[4, 1000, u64::MAX] {
+            let original: Vec<_> = std::iter::from_fn(|| Some(rng.next_u64() % modulus))
+                .take(500)
+                .collect();
+            let mut sorted = original.clone();
+            sorted.sort();
+            let misplaced = (0..500).filter(|&i| original[i] != sorted[i]).count();
+            let writes = Cell::new(0);
+            let mut array = Counting(original, &writes);
+            cycle_sort(&mut array, Ord::cmp);
+            assert_eq!(sorted, array.0);
+            assert!(writes.get() <= misplaced);
+        }
+    }
+
+    #[test]
+    fn test_cycle_sort_panic_safety() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: Vec<String> = std::iter::from_fn(move || Some(rng.next_u64() % 50))
+            .map(|n| n.to_string())
+            .take(100)
+            .collect();
+        for limit in &[0, 1, 10, 100, 1000] {
+            let mut vec: VecDeque<_> = original.iter().cloned().collect();
+            let mut count = 0;
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                cycle_sort(&mut vec, |l: &String, r: &String| {
+                    count += 1;
+                    if count > *limit {
+                        panic!("comparator panic");
+                    }
+                    l.cmp(r)
+                });
+            }));
+            assert!(result.is_err());
+            let mut remaining: Vec<_> = vec.into_iter().collect();
+            let mut expected = original.clone();
+            remaining.sort();
+            expected.sort();
+            assert_eq!(expected, remaining);
+        }
+    }
+
+    #[test]
+    fn test_sort_spec() {
+        let mut vec: VecDeque<_> = vec![(2, 'b'), (1, 'z'), (2, 'a'), (1, 'y'), (3, 'c')].into();
+        sort_by_permutation(&mut vec, |l, r| l.0.cmp(&r.0), true);
+        assert_eq!(vec, vec![(1, 'z'), (1, 'y'), (2, 'b'), (2, 'a'), (3, 'c')]);
+        let spec = SortSpec::by_key(|p: &(i32, char)| p.0)
+            .desc()
+            .then_by(|l: &(i32, char), r| l.1.cmp(&r.1));
+        sort_by_permutation(&mut vec, |l, r| spec.compare(l, r), true);
+        assert_eq!(vec, vec![(3, 'c'), (2, 'a'), (2, 'b'), (1, 'y'), (1, 'z')]);
+        let spec = SortSpec::by_key(|p: &(i32, char)| p.0).desc().asc();
+        assert_eq!(Ordering::Less, spec.compare(&(1, 'a'), &(2, 'a')));
+    }
+
+    #[test]
+    fn test_sort_by_permutation() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: Vec<String> = std::iter::from_fn(move || Some(rng.next_u64() % 100))
+            .map(|n| n.to_string())
+            .take(500)
+            .collect();
+        let mut expected = original.clone();
+        expected.sort();
+        for &stable in &[false, true] {
+            let mut vec: VecDeque<_> = original.iter().cloned().collect();
+            sort_by_permutation(&mut vec, Ord::cmp, stable);
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn test_sort_with_options() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: Vec<(u64, usize)> =
+            (0..500).map(|index| (rng.next_u64() % 50, index)).collect();
+        let mut expected = original.clone();
+        expected.sort_by_key(|pair| pair.0);
+        let by_key = |l: &(u64, usize), r: &(u64, usize)| l.0.cmp(&r.0);
+        let pivots = [
+            PivotChoice::Random(7),
+            PivotChoice::First,
+            PivotChoice::MedianOfThree,
+            PivotChoice::Ninther,
+        ];
+        for &pivot in &pivots {
+            for &threshold in &[0, 1, 24, 1000] {
+                for &algorithm in &[SortAlgorithm::Quicksort, SortAlgorithm::Indirect] {
+                    let options = SortOptions::new()
+                        .algorithm(algorithm)
+                        .pivot(pivot)
+                        .small_array_threshold(threshold);
+                    let mut vec: VecDeque<_> = original.iter().cloned().collect();
+                    sort_with_options(&mut vec, options, by_key);
+                    assert!(vec.is_sorted_by_key(|pair| pair.0));
+                    let mut vec: VecDeque<_> = original.iter().cloned().collect();
+                    sort_with_options(&mut vec, options.stable(true), by_key);
+                    assert_eq!(vec, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_sort() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        for &len in &[0, 1, 2, 15, 16, 17, 100, 1000] {
+            let original: Vec<(u64, usize)> =
+                (0..len).map(|index| (rng.next_u64() % 20, index)).collect();
+            let mut expected = original.clone();
+            expected.sort_by_key(|pair| pair.0);
+            let mut vec: VecDeque<_> = original.into_iter().collect();
+            merge_sort(&mut vec, |l, r| l.0.cmp(&r.0));
+            assert_eq!(vec, expected);
+        }
+    }
+
+    #[test]
+    fn test_merge_sort_panic_safety() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let original: VecDeque<String> = std::iter::from_fn(|| Some(rng.next_u64() % 1000))
+            .map(|n| n.to_string())
+            .take(200)
+            .collect();
+        for &limit in &[10, 500, 1000] {
+            let mut vec = original.clone();
+            let mut count = 0;
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                merge_sort(&mut vec, |l: &String, r: &String| {
+                    count += 1;
+                    if count > limit {
+                        panic!("comparator panic");
+                    }
+                    l.cmp(r)
+                })
+            }));
+            assert!(result.is_err());
+            let mut remaining = Vec::from(vec);
+            let mut expected = Vec::from(original.clone());
+            remaining.sort();
+            expected.sort();
+            assert_eq!(expected, remaining);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_cached_key() {
+        let mut vec: VecDeque<_> = vec!["ccc", "a", "bb", "dddd", ""].into();
+        let mut calls = 0;
+        sort_by_cached_key(&mut vec, |s| {
+            calls += 1;
+            s.len()
+        });
+        assert_eq!(vec, vec!["", "a", "bb", "ccc", "dddd"]);
+        assert_eq!(5, calls);
+    }
+
+    #[test]
+    fn test_introsort_worst_case() {
+        // `FirstPivot` is quadratic on this input, which takes about two
+        // million comparisons, unless the heapsort fallback kicks in.
+        let original: Vec<usize> = (0..1000).chain((0..1000).rev()).collect();
+        let mut expected = original.clone();
+        expected.sort();
+        for &threshold in &[0, INSERTION_SORT_THRESHOLD] {
+            let mut vec: VecDeque<_> = original.iter().cloned().collect();
+            let mut count = 0;
+            quicksort_with_threshold(
+                &mut vec,
+                0,
+                1999,
+                |l: &usize, r: &usize| {
+                    count += 1;
+                    l.cmp(r)
+                },
+                &mut FirstPivot,
+                threshold,
+            );
+            assert_eq!(vec, expected);
+            assert!(count < 200_000, "too many comparisons: {}", count);
+        }
+    }
+
+    #[test]
+    fn test_quicksort_large() {
+        let mut rng = rand_xoshiro::Xoshiro256PlusPlus::seed_from_u64(1337);
+        let mut vec: VecDeque<u64> = (0..100_000).map(|_| rng.next_u64() % 1000).collect();
+        let mut expected: Vec<_> = vec.iter().cloned().collect();
+        expected.sort();
+        let last = vec.len() - 1;
+        quicksort_with_threshold(&mut vec, 0, last, Ord::cmp, &mut FirstPivot, 0);
+        assert_eq!(vec, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "pivot index")]
+    fn test_pivot_out_of_range() {
+        struct PastTheEnd;
+
+        impl PivotStrategy for PastTheEnd {
+            fn choose_pivot<Arr, F>(
+                &mut self,
+                _array: &Arr,
+                _left: usize,
+                right: usize,
+                _cmp: &mut F,
+            ) -> usize
+            where
+                Arr: Array + ?Sized,
+                F: FnMut(
+                    &<Arr as Index<usize>>::Output,
+                    &<Arr as Index<usize>>::Output,
+                ) -> Ordering,
+            {
+                right + 100
+            }
+        }
+
+        let mut vec: VecDeque<String> = (0..200).map(|n| (n * 37 % 200).to_string()).collect();
+        quicksort_with(&mut vec, 0, 199, Ord::cmp, &mut PastTheEnd);
+    }
 }
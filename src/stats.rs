@@ -0,0 +1,293 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::ops::Index;
+
+use crate::array::Array;
+
+/// Trait for primitive numeric types which can be converted into an `f64`
+/// for computing statistics.
+///
+/// The conversion is an `as` cast. It's exact for every type except the
+/// 64 and 128 bit integers, `usize` and `isize`, whose values beyond 2^53
+/// are rounded to the nearest representable `f64`.
+pub trait AsF64: Copy {
+    /// Convert the value into an `f64`.
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_as_f64 {
+    ($($t:ty),*) => {
+        $(
+            impl AsF64 for $t {
+                fn as_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_as_f64!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+/// Running totals for Welford's single pass mean and variance algorithm.
+#[derive(Clone, Copy, Default)]
+struct Welford {
+    count: usize,
+    mean: f64,
+    m2: f64,
+}
+
+impl Welford {
+    fn push(mut self, value: f64) -> Self {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (value - self.mean);
+        self
+    }
+
+    fn variance(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.m2 / self.count as f64)
+        }
+    }
+}
+
+/// The smallest element, the largest element and the mean of an array, as
+/// returned by [`ArrayStats::min_max_mean`][min_max_mean].
+///
+/// [min_max_mean]: trait.ArrayStats.html#method.min_max_mean
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Summary<A> {
+    /// The smallest element.
+    pub min: A,
+    /// The largest element.
+    pub max: A,
+    /// The arithmetic mean of the elements.
+    pub mean: f64,
+}
+
+/// Summary statistics for arrays of numbers.
+///
+/// This trait is implemented for every [`Array`][Array] whose elements are
+/// primitive numbers, as described by [`AsF64`][AsF64], and all statistics
+/// are computed as `f64`s. Large 64 and 128 bit integers are rounded when
+/// they're converted.
+///
+/// A single NaN makes the mean and variance NaN, and makes the result of
+/// comparisons against it meaningless. For arrays of `f32` or `f64` which may
+/// contain NaNs, [`min_ignore_nan`][min_ignore_nan] and
+/// [`max_ignore_nan`][max_ignore_nan] find the extremes of the remaining
+/// elements, and [`nan_count`][nan_count] counts the NaNs.
+///
+/// [min_ignore_nan]: #method.min_ignore_nan
+/// [max_ignore_nan]: #method.max_ignore_nan
+/// [nan_count]: #method.nan_count
+///
+/// [Array]: trait.Array.html
+/// [AsF64]: trait.AsF64.html
+pub trait ArrayStats: Array
+where
+    <Self as Index<usize>>::Output: AsF64,
+{
+    /// Compute the arithmetic mean of the elements in the array.
+    ///
+    /// Returns `None` if the array is empty.
+    fn mean(&self) -> Option<f64> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(
+                self.fold(Welford::default(), |acc, value| acc.push(value.as_f64()))
+                    .mean,
+            )
+        }
+    }
+
+    /// Compute the population variance of the elements in the array.
+    ///
+    /// This uses Welford's algorithm, which makes a single pass over the array
+    /// and avoids the loss of precision of the naive sum of squares.
+    ///
+    /// Returns `None` if the array is empty.
+    fn variance(&self) -> Option<f64> {
+        self.fold(Welford::default(), |acc, value| acc.push(value.as_f64()))
+            .variance()
+    }
+
+    /// Compute the population standard deviation of the elements in the array.
+    ///
+    /// Returns `None` if the array is empty.
+    fn stddev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    /// Find the smallest element, the largest element and the mean of the
+    /// array in a single pass.
+    ///
+    /// If several elements are equally minimal or maximal, the first of them
+    /// is returned. NaNs are ignored when finding the extremes but still
+    /// included in the mean, unless every element is NaN, in which case the
+    /// extremes are NaN too.
+    ///
+    /// Returns `None` if the array is empty.
+    fn min_max_mean(&self) -> Option<Summary<<Self as Index<usize>>::Output>>
+    where
+        <Self as Index<usize>>::Output: PartialOrd,
+    {
+        let first = *self.first()?;
+        let (extremes, stats) =
+            self.fold((None, Welford::default()), |(extremes, stats), value| {
+                let value = *value;
+                let number = value.as_f64();
+                let extremes = match extremes {
+                    _ if number.is_nan() => extremes,
+                    Some((min, max)) => Some((
+                        if value < min { value } else { min },
+                        if value > max { value } else { max },
+                    )),
+                    None => Some((value, value)),
+                };
+                (extremes, stats.push(number))
+            });
+        let (min, max) = extremes.unwrap_or((first, first));
+        Some(Summary {
+            min,
+            max,
+            mean: stats.mean,
+        })
+    }
+
+    /// Count the elements of the array which are NaN.
+    ///
+    /// This is always zero for arrays of integers.
+    fn nan_count(&self) -> usize {
+        self.fold(0, |count, value| {
+            if value.as_f64().is_nan() {
+                count + 1
+            } else {
+                count
+            }
+        })
+    }
+
+    /// Find the smallest element of the array, skipping any NaNs.
+    ///
+    /// If several elements are equally minimal, the first of them is returned.
+    ///
+    /// Returns `None` if the array is empty or contains only NaNs.
+    fn min_ignore_nan(&self) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: PartialOrd,
+    {
+        self.fold(None, |min, value| match min {
+            _ if value.as_f64().is_nan() => min,
+            Some(min) if min <= *value => Some(min),
+            _ => Some(*value),
+        })
+    }
+
+    /// Find the largest element of the array, skipping any NaNs.
+    ///
+    /// If several elements are equally maximal, the first of them is returned.
+    ///
+    /// Returns `None` if the array is empty or contains only NaNs.
+    fn max_ignore_nan(&self) -> Option<<Self as Index<usize>>::Output>
+    where
+        <Self as Index<usize>>::Output: PartialOrd,
+    {
+        self.fold(None, |max, value| match max {
+            _ if value.as_f64().is_nan() => max,
+            Some(max) if max >= *value => Some(max),
+            _ => Some(*value),
+        })
+    }
+}
+
+impl<A> ArrayStats for A
+where
+    A: Array + ?Sized,
+    <A as Index<usize>>::Output: AsF64,
+{
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn summary_statistics() {
+        let vec: VecDeque<u32> = vec![2, 4, 4, 4, 5, 5, 7, 9].into();
+        assert_eq!(Some(5.0), vec.mean());
+        assert_eq!(Some(4.0), vec.variance());
+        assert_eq!(Some(2.0), vec.stddev());
+        assert_eq!(
+            Some(Summary {
+                min: 2,
+                max: 9,
+                mean: 5.0
+            }),
+            vec.min_max_mean()
+        );
+
+        let empty: VecDeque<f64> = VecDeque::new();
+        assert_eq!(None, empty.mean());
+        assert_eq!(None, empty.variance());
+        assert_eq!(None, empty.stddev());
+        assert_eq!(None, empty.min_max_mean());
+    }
+
+    #[test]
+    fn wide_integers() {
+        let vec: VecDeque<u64> = vec![1, 2, 3, u64::MAX].into();
+        assert_eq!(
+            Some(Summary {
+                min: 1,
+                max: u64::MAX,
+                mean: (6.0 + u64::MAX as f64) / 4.0
+            }),
+            vec.min_max_mean()
+        );
+        let vec: VecDeque<i64> = vec![-3, 1, 8].into();
+        assert_eq!(Some(2.0), vec.mean());
+        let vec: VecDeque<usize> = vec![2, 4].into();
+        assert_eq!(Some(1.0), vec.variance());
+        let vec: VecDeque<isize> = vec![-5, 5].into();
+        assert_eq!(0, vec.nan_count());
+    }
+
+    #[test]
+    fn variance_is_numerically_stable() {
+        let vec: VecDeque<f64> = vec![1e9 + 4.0, 1e9 + 7.0, 1e9 + 13.0, 1e9 + 16.0].into();
+        assert_eq!(Some(22.5), vec.variance());
+    }
+
+    #[test]
+    fn nan_aware_reductions() {
+        let vec: VecDeque<f64> = vec![f64::NAN, 3.0, -1.0, f64::NAN, 7.5, 2.0].into();
+        assert_eq!(2, vec.nan_count());
+        assert_eq!(Some(-1.0), vec.min_ignore_nan());
+        assert_eq!(Some(7.5), vec.max_ignore_nan());
+
+        let nans: VecDeque<f32> = vec![f32::NAN, f32::NAN].into();
+        assert_eq!(2, nans.nan_count());
+        assert_eq!(None, nans.min_ignore_nan());
+        assert_eq!(None, nans.max_ignore_nan());
+
+        let ints: VecDeque<i32> = vec![4, -2, 9].into();
+        assert_eq!(0, ints.nan_count());
+        assert_eq!(Some(-2), ints.min_ignore_nan());
+        assert_eq!(Some(9), ints.max_ignore_nan());
+
+        let summary = vec.min_max_mean().unwrap();
+        assert_eq!((-1.0, 7.5), (summary.min, summary.max));
+        assert!(summary.mean.is_nan());
+        let summary = nans.min_max_mean().unwrap();
+        assert!(summary.min.is_nan() && summary.max.is_nan());
+    }
+}
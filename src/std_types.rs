@@ -2,10 +2,12 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::cmp::Ordering;
 use std::collections::VecDeque;
+use std::mem::ManuallyDrop;
 use std::ops::Index;
 
-use crate::array::{Array, ArrayMut, HasLength};
+use crate::array::{Array, ArrayMut, ArrayResize, DisjointIndexMut, HasLength};
 
 // VecDeque
 
@@ -26,6 +28,88 @@ impl<A> Array for VecDeque<A> {
     {
         VecDeque::contains(self, target)
     }
+
+    fn position<F>(&self, predicate: F) -> Option<usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> bool,
+    {
+        self.iter().position(predicate)
+    }
+
+    fn fold<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, &<Self as Index<usize>>::Output) -> B,
+    {
+        let (front, back) = self.as_slices();
+        let acc = front.iter().fold(init, &mut f);
+        back.iter().fold(acc, f)
+    }
+
+    fn mismatch<O>(&self, other: &O) -> Option<usize>
+    where
+        O: Array + Index<usize, Output = <Self as Index<usize>>::Output> + ?Sized,
+        <Self as Index<usize>>::Output: PartialEq,
+    {
+        let len = VecDeque::len(self).min(other.len());
+        match self
+            .iter()
+            .take(len)
+            .enumerate()
+            .position(|(index, value)| *value != other[index])
+        {
+            None if VecDeque::len(self) == other.len() => None,
+            None => Some(len),
+            found => found,
+        }
+    }
+
+    fn binary_search_by<F>(&self, mut compare: F) -> Result<usize, usize>
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> Ordering,
+    {
+        #[cfg(all(feature = "validate-ordering", debug_assertions))]
+        crate::validate::check_search(self, &mut compare);
+        let (front, back) = self.as_slices();
+        match back.first().map(&mut compare) {
+            Some(Ordering::Less) | Some(Ordering::Equal) => back
+                .binary_search_by(compare)
+                .map(|index| index + front.len())
+                .map_err(|index| index + front.len()),
+            _ => front.binary_search_by(compare),
+        }
+    }
+
+    fn starts_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        if slice.len() > VecDeque::len(self) {
+            return false;
+        }
+        let (front, back) = self.as_slices();
+        if slice.len() <= front.len() {
+            front.starts_with(slice)
+        } else {
+            let (slice_front, slice_back) = slice.split_at(front.len());
+            front == slice_front && back.starts_with(slice_back)
+        }
+    }
+
+    fn ends_with(&self, slice: &[<Self as Index<usize>>::Output]) -> bool
+    where
+        <Self as Index<usize>>::Output: PartialEq + Sized,
+    {
+        if slice.len() > VecDeque::len(self) {
+            return false;
+        }
+        let (front, back) = self.as_slices();
+        if slice.len() <= back.len() {
+            back.ends_with(slice)
+        } else {
+            let (slice_front, slice_back) = slice.split_at(slice.len() - back.len());
+            back == slice_back && front.ends_with(slice_front)
+        }
+    }
 }
 
 impl<A> ArrayMut for VecDeque<A> {
@@ -39,6 +123,94 @@ impl<A> ArrayMut for VecDeque<A> {
     {
         VecDeque::swap(self, index1, index2)
     }
+
+    fn sort_unstable(&mut self)
+    where
+        <Self as Index<usize>>::Output: Ord + Sized,
+    {
+        self.sort_unstable_by(Ord::cmp)
+    }
+
+    fn sort_unstable_by<F>(&mut self, compare: F)
+    where
+        <Self as Index<usize>>::Output: Sized,
+        F: FnMut(&<Self as Index<usize>>::Output, &<Self as Index<usize>>::Output) -> Ordering,
+    {
+        #[cfg(all(feature = "validate-ordering", debug_assertions))]
+        let compare = crate::validate::comparator(compare);
+        self.make_contiguous().sort_unstable_by(compare)
+    }
+
+    fn sort_unstable_by_key<F, K>(&mut self, mut extract: F)
+    where
+        F: FnMut(&<Self as Index<usize>>::Output) -> K,
+        K: Ord,
+        <Self as Index<usize>>::Output: Sized,
+    {
+        self.sort_unstable_by(|l, r| extract(l).cmp(&extract(r)))
+    }
+
+    unsafe fn get_unchecked_ptr_mut(this: *mut Self, index: usize) -> *mut A {
+        // Index a bitwise copy of the deque, which shares its buffer, so that
+        // no reference to the deque itself is created. The copy must not be
+        // dropped.
+        let mut copy = ManuallyDrop::new(std::ptr::read(this));
+        VecDeque::get_mut(&mut copy, index).unwrap_unchecked()
+    }
+
+    fn get2_mut(&mut self, index1: usize, index2: usize) -> Option<(&mut A, &mut A)> {
+        let len = VecDeque::len(self);
+        if index1 == index2 || index1 >= len || index2 >= len {
+            return None;
+        }
+        let (low, high) = (index1.min(index2), index1.max(index2));
+        let (front, back) = self.as_mut_slices();
+        let (low_ref, high_ref) = if high < front.len() {
+            let (left, right) = front.split_at_mut(high);
+            (&mut left[low], &mut right[0])
+        } else if low >= front.len() {
+            let (left, right) = back.split_at_mut(high - front.len());
+            (&mut left[low - front.len()], &mut right[0])
+        } else {
+            let offset = front.len();
+            (&mut front[low], &mut back[high - offset])
+        };
+        if index1 < index2 {
+            Some((low_ref, high_ref))
+        } else {
+            Some((high_ref, low_ref))
+        }
+    }
+}
+
+// A `VecDeque` keeps its elements in a heap allocated ring buffer, which
+// `index_mut` only reads the deque to locate.
+unsafe impl<A> DisjointIndexMut for VecDeque<A> {}
+
+impl<A> ArrayResize for VecDeque<A> {
+    fn insert(&mut self, index: usize, value: A) {
+        VecDeque::insert(self, index, value)
+    }
+
+    fn remove(&mut self, index: usize) -> Option<A> {
+        VecDeque::remove(self, index)
+    }
+
+    fn push(&mut self, value: A) {
+        VecDeque::push_back(self, value)
+    }
+
+    fn pop(&mut self) -> Option<A> {
+        VecDeque::pop_back(self)
+    }
+
+    fn truncate(&mut self, len: usize) {
+        VecDeque::truncate(self, len)
+    }
+
+    fn clear(&mut self) {
+        VecDeque::clear(self)
+    }
 }
 
 #[cfg(test)]
@@ -54,5 +226,69 @@ mod test {
         ArrayMut::sort_unstable(&mut vec);
         assert_eq!(Some(&1), Array::first(&vec));
         assert_eq!(Some(&3), Array::last(&vec));
+        ArrayResize::push(&mut vec, 4);
+        ArrayResize::insert(&mut vec, 0, 0);
+        assert_eq!(Some(4), ArrayResize::pop(&mut vec));
+        assert_eq!(Some(0), ArrayResize::remove(&mut vec, 0));
+        assert_eq!(3, HasLength::len(&vec));
+    }
+
+    #[test]
+    fn vec_deque_split_storage() {
+        // Rotating puts the elements on both sides of the ring buffer's seam.
+        let mut vec: VecDeque<_> = VecDeque::with_capacity(8);
+        vec.extend(10..16);
+        vec.drain(..4);
+        vec.extend(16..22);
+        assert!(!vec.as_slices().1.is_empty());
+        let expected: Vec<_> = (14..22).collect();
+        for len in 0..=expected.len() {
+            assert!(Array::starts_with(&vec, &expected[..len]));
+            assert!(Array::ends_with(&vec, &expected[expected.len() - len..]));
+        }
+        assert!(!Array::starts_with(&vec, &[14, 15, 17]));
+        assert!(!Array::ends_with(&vec, &[15, 20, 21]));
+        for target in 13..23 {
+            assert_eq!(
+                expected.binary_search(&target),
+                Array::binary_search(&vec, &target)
+            );
+        }
+        assert_eq!(Some(3), Array::position(&vec, |n| *n == 17));
+        assert_eq!(
+            expected.iter().sum::<i32>(),
+            Array::fold(&vec, 0, |a, n| a + n)
+        );
+        let other: VecDeque<_> = vec![14, 15, 16, 17, 0].into();
+        assert_eq!(Some(4), Array::mismatch(&vec, &other));
+    }
+
+    #[test]
+    fn vec_deque_sort() {
+        let mut vec: VecDeque<_> = vec![8, 1, 7, 2, 9].into();
+        vec.push_front(4);
+        assert!(!vec.as_slices().1.is_empty());
+        ArrayMut::sort_unstable(&mut vec);
+        assert_eq!(vec![1, 2, 4, 7, 8, 9], Vec::from(vec.clone()));
+        ArrayMut::sort_unstable_by_key(&mut vec, |n| -n);
+        assert_eq!(vec![9, 8, 7, 4, 2, 1], Vec::from(vec));
+    }
+
+    #[test]
+    fn vec_deque_map_pair() {
+        let mut vec: VecDeque<_> = (0..4).collect();
+        vec.rotate_left(2);
+        vec.push_front(9);
+        for (index1, index2) in &[(0, 1), (4, 0), (1, 4), (3, 2), (2, 4)] {
+            let expected = (vec[*index1], vec[*index2]);
+            assert_eq!(
+                expected,
+                ArrayMut::map_pair(&mut vec, *index1, *index2, |a, b| (*a, *b))
+            );
+            let (a, b) = ArrayMut::get2_mut(&mut vec, *index1, *index2).unwrap();
+            assert_eq!(expected, (*a, *b));
+        }
+        assert!(ArrayMut::get2_mut(&mut vec, 2, 2).is_none());
+        assert!(ArrayMut::get2_mut(&mut vec, 0, 5).is_none());
     }
 }
@@ -0,0 +1,218 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::cmp::Ordering;
+use std::convert::Infallible;
+use std::ops::Index;
+
+use crate::array::{Array, HasLength};
+
+/// Trait for data structures which are indexed like arrays, but whose
+/// element access can fail, such as memory mapped, lazily paged or network
+/// backed storage.
+///
+/// Only `try_get` needs to be implemented; the other methods are fallible
+/// versions of the corresponding `Array` methods, which stop and return the
+/// error as soon as an access fails.
+///
+/// Every `Array` implements `TryArray` with `Infallible` as its error type.
+pub trait TryArray: HasLength {
+    /// The type of the elements of the array.
+    type Element: ?Sized;
+
+    /// The type of error returned when an access fails.
+    type Error;
+
+    /// Get a reference to the element at the given index.
+    ///
+    /// The provided methods only call this with indexes less than
+    /// `self.len()`. What happens for other indexes is up to the
+    /// implementor.
+    fn try_get(&self, index: usize) -> Result<&Self::Element, Self::Error>;
+
+    /// Get a reference to the first element in the array.
+    fn try_first(&self) -> Result<Option<&Self::Element>, Self::Error> {
+        if self.is_empty() {
+            Ok(None)
+        } else {
+            self.try_get(0).map(Some)
+        }
+    }
+
+    /// Get a reference to the last element in the array.
+    fn try_last(&self) -> Result<Option<&Self::Element>, Self::Error> {
+        if self.is_empty() {
+            Ok(None)
+        } else {
+            self.try_get(self.len() - 1).map(Some)
+        }
+    }
+
+    /// Return true if an element equivalent to `target` exists in the array.
+    fn try_contains(&self, target: &Self::Element) -> Result<bool, Self::Error>
+    where
+        Self::Element: PartialEq,
+    {
+        self.try_position(|element| element == target)
+            .map(|found| found.is_some())
+    }
+
+    /// Return the index of the first element for which `predicate` returns
+    /// `true`, or `None` if there is no such element.
+    fn try_position<F>(&self, mut predicate: F) -> Result<Option<usize>, Self::Error>
+    where
+        F: FnMut(&Self::Element) -> bool,
+    {
+        for index in 0..self.len() {
+            if predicate(self.try_get(index)?) {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Perform a binary search for `target`.
+    fn try_binary_search(&self, target: &Self::Element) -> Result<Result<usize, usize>, Self::Error>
+    where
+        Self::Element: Ord,
+    {
+        self.try_binary_search_by(|element| element.cmp(target))
+    }
+
+    /// Perform a binary search using a comparator function.
+    fn try_binary_search_by<F>(&self, mut compare: F) -> Result<Result<usize, usize>, Self::Error>
+    where
+        F: FnMut(&Self::Element) -> Ordering,
+    {
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match compare(self.try_get(mid)?) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(Ok(mid)),
+            }
+        }
+        Ok(Err(low))
+    }
+
+    /// Perform a binary search using a key and a key extractor function.
+    fn try_binary_search_by_key<K, F>(
+        &self,
+        key: &K,
+        mut extract: F,
+    ) -> Result<Result<usize, usize>, Self::Error>
+    where
+        F: FnMut(&Self::Element) -> K,
+        K: Ord,
+    {
+        self.try_binary_search_by(|element| extract(element).cmp(key))
+    }
+
+    /// Test whether the array starts with the elements in `slice`.
+    fn try_starts_with(&self, slice: &[Self::Element]) -> Result<bool, Self::Error>
+    where
+        Self::Element: PartialEq + Sized,
+    {
+        if slice.len() > self.len() {
+            return Ok(false);
+        }
+        for (index, expected) in slice.iter().enumerate() {
+            if self.try_get(index)? != expected {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Test whether this array and `other` have the same length and equal
+    /// elements.
+    fn try_eq<O>(&self, other: &O) -> Result<bool, Self::Error>
+    where
+        O: TryArray<Element = Self::Element, Error = Self::Error> + ?Sized,
+        Self::Element: PartialEq,
+    {
+        if self.len() != other.len() {
+            return Ok(false);
+        }
+        for index in 0..self.len() {
+            if self.try_get(index)? != other.try_get(index)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<A> TryArray for A
+where
+    A: Array + ?Sized,
+{
+    type Element = <A as Index<usize>>::Output;
+    type Error = Infallible;
+
+    fn try_get(&self, index: usize) -> Result<&Self::Element, Self::Error> {
+        Ok(&self[index])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// An array of squares which can't read past a fixed index, standing in
+    /// for storage which fails partway through.
+    struct Flaky {
+        len: usize,
+        readable: usize,
+        values: Vec<usize>,
+    }
+
+    impl HasLength for Flaky {
+        fn len(&self) -> usize {
+            self.len
+        }
+    }
+
+    impl TryArray for Flaky {
+        type Element = usize;
+        type Error = usize;
+
+        fn try_get(&self, index: usize) -> Result<&usize, usize> {
+            if index < self.readable {
+                Ok(&self.values[index])
+            } else {
+                Err(index)
+            }
+        }
+    }
+
+    #[test]
+    fn try_array() {
+        let flaky = Flaky {
+            len: 10,
+            readable: 6,
+            values: (0..10).map(|n| n * n).collect(),
+        };
+        assert_eq!(Ok(Some(&0)), flaky.try_first());
+        assert_eq!(Err(9), flaky.try_last());
+        assert_eq!(Ok(true), flaky.try_contains(&16));
+        assert_eq!(Err(6), flaky.try_contains(&17));
+        assert_eq!(Ok(Ok(2)), flaky.try_binary_search(&4));
+        assert_eq!(Err(8), flaky.try_binary_search(&64));
+        assert_eq!(Ok(true), flaky.try_starts_with(&[0, 1, 4]));
+        assert_eq!(Err(6), flaky.try_eq(&flaky));
+    }
+
+    #[test]
+    fn infallible_arrays() {
+        let vec: VecDeque<_> = vec![1, 3, 5].into();
+        assert_eq!(Ok(Some(&5)), vec.try_last());
+        assert_eq!(Ok(Err(1)), vec.try_binary_search(&2));
+        assert_eq!(Ok(Some(1)), vec.try_position(|n| *n > 1));
+        assert_eq!(Ok(true), vec.try_eq(&vec.clone()));
+    }
+}
@@ -0,0 +1,177 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Consistency checks for user supplied comparators, enabled by the
+//! `validate-ordering` feature in debug builds.
+
+use std::cmp::Ordering;
+use std::ops::{Index, Range};
+
+use rand_core::RngCore;
+
+use crate::array::Array;
+use crate::sort::gen_range;
+
+const TRANSITIVITY_SAMPLES: usize = 64;
+const SEARCH_SAMPLES: usize = 64;
+
+/// Wrap a comparator so that every comparison also checks that comparing the
+/// arguments in reverse order gives the reverse result.
+pub(crate) fn comparator<A, F>(mut cmp: F) -> impl FnMut(&A, &A) -> Ordering
+where
+    A: ?Sized,
+    F: FnMut(&A, &A) -> Ordering,
+{
+    move |left, right| {
+        let result = cmp(left, right);
+        let reverse = cmp(right, left);
+        if result != reverse.reverse() {
+            panic!(
+                "inconsistent comparator: compare(a, b) returned {:?} but compare(b, a) returned {:?}",
+                result, reverse
+            );
+        }
+        result
+    }
+}
+
+/// Check the comparator for reflexivity and transitivity on randomly sampled
+/// triples of elements from `range`.
+pub(crate) fn check_transitivity<Arr, F, R>(
+    array: &Arr,
+    range: Range<usize>,
+    cmp: &mut F,
+    rng: &mut R,
+) where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output, &<Arr as Index<usize>>::Output) -> Ordering,
+    R: RngCore,
+{
+    if range.start >= range.end {
+        return;
+    }
+    for _ in 0..TRANSITIVITY_SAMPLES.min(range.end - range.start) {
+        let a = gen_range(rng, range.start, range.end);
+        let b = gen_range(rng, range.start, range.end);
+        let c = gen_range(rng, range.start, range.end);
+        let aa = cmp(&array[a], &array[a]);
+        if aa != Ordering::Equal {
+            panic!(
+                "inconsistent comparator: element at index {} compares {:?} to itself",
+                a, aa
+            );
+        }
+        let ab = cmp(&array[a], &array[b]);
+        let bc = cmp(&array[b], &array[c]);
+        let ac = cmp(&array[a], &array[c]);
+        let expected = match (ab, bc) {
+            (Ordering::Equal, other) | (other, Ordering::Equal) => Some(other),
+            (left, right) if left == right => Some(left),
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            if ac != expected {
+                panic!(
+                    "inconsistent comparator: elements at indexes {}, {} and {} compare {:?}, {:?} and {:?}, which is not transitive",
+                    a, b, c, ab, bc, ac
+                );
+            }
+        }
+    }
+}
+
+/// Check that the array is ordered consistently with a binary search
+/// comparator, ie. that the comparator's results never decrease from the
+/// front to the back of the array.
+///
+/// Arrays longer than `SEARCH_SAMPLES` are only checked at evenly spaced
+/// elements, so that the check doesn't turn every O(log n) search into an
+/// O(n) scan.
+pub(crate) fn check_search<Arr, F>(array: &Arr, compare: &mut F)
+where
+    Arr: Array + ?Sized,
+    F: FnMut(&<Arr as Index<usize>>::Output) -> Ordering,
+{
+    let len = array.len();
+    if len == 0 {
+        return;
+    }
+    let step = (len / SEARCH_SAMPLES).max(1);
+    let mut previous = Ordering::Less;
+    for index in (0..len).step_by(step).chain(Some(len - 1)) {
+        let current = compare(&array[index]);
+        if current < previous {
+            panic!(
+                "binary search on an unsorted array: element at index {} compares {:?} but a preceding element compares {:?}",
+                index, current, previous
+            );
+        }
+        previous = current;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::array::{Array, ArrayMut};
+    use std::collections::VecDeque;
+
+    #[test]
+    #[should_panic(expected = "inconsistent comparator")]
+    fn asymmetric_comparator() {
+        let mut vec: VecDeque<_> = (0..100).rev().collect();
+        vec.sort_unstable_by(|_, _| std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    #[should_panic(expected = "inconsistent comparator")]
+    fn asymmetric_key_ordering() {
+        #[derive(PartialEq, Eq)]
+        struct Key;
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+        impl Ord for Key {
+            fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
+                std::cmp::Ordering::Less
+            }
+        }
+        let mut vec: VecDeque<_> = (0..100).rev().collect();
+        vec.sort_unstable_by_key(|_| Key);
+    }
+
+    #[test]
+    #[should_panic(expected = "binary search on an unsorted array")]
+    fn unsorted_search() {
+        let vec: VecDeque<_> = vec![1, 5, 3, 7].into();
+        let _ = Array::binary_search(&vec, &5);
+    }
+
+    #[test]
+    #[should_panic(expected = "binary search on an unsorted array")]
+    fn unsorted_large_search() {
+        let vec: VecDeque<_> = (0..10_000).rev().collect();
+        let _ = Array::binary_search(&vec, &5000);
+    }
+
+    #[test]
+    fn search_check_is_bounded() {
+        let vec: VecDeque<_> = (0..100_000).collect();
+        let mut comparisons = 0;
+        let result = Array::binary_search_by(&vec, |value| {
+            comparisons += 1;
+            value.cmp(&42)
+        });
+        assert_eq!(Ok(42), result);
+        assert!(comparisons < 4 * super::SEARCH_SAMPLES);
+    }
+
+    #[test]
+    fn consistent_comparator() {
+        let mut vec: VecDeque<_> = (0..100).rev().collect();
+        vec.sort_unstable();
+        assert_eq!(Ok(42), Array::binary_search(&vec, &42));
+    }
+}
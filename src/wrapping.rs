@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::ops::{Index, IndexMut};
+
+use crate::array::{Array, ArrayMut, HasLength};
+
+/// A view of an array as a circular sequence.
+///
+/// A `Wrapping` has a fixed logical length, which may be greater than the
+/// length of the array it wraps. Indexes into it wrap around modulo the
+/// length of the underlying array, so that index `array.len()` refers back to
+/// the first element of the array, and so on.
+///
+/// Because several indexes of a `Wrapping` can refer to the same element, it
+/// implements `IndexMut`, but not `ArrayMut`, whose methods rely on distinct
+/// indexes referring to distinct elements.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Wrapping<Arr> {
+    array: Arr,
+    len: usize,
+}
+
+impl<Arr> Wrapping<Arr>
+where
+    Arr: HasLength,
+{
+    /// Construct a circular view of `array` with the logical length `len`.
+    ///
+    /// Panics if `array` is empty and `len` is not zero.
+    pub fn new(array: Arr, len: usize) -> Self {
+        if len > 0 && array.is_empty() {
+            panic!("Wrapping::new: cannot wrap an empty array!");
+        }
+        Wrapping { array, len }
+    }
+
+    /// Get a reference to the wrapped array.
+    pub fn as_inner(&self) -> &Arr {
+        &self.array
+    }
+
+    /// Discard the view and return the wrapped array.
+    pub fn into_inner(self) -> Arr {
+        self.array
+    }
+
+    fn wrap_index(&self, index: usize) -> usize {
+        if index >= self.len {
+            panic!(
+                "Wrapping::index: index {} out of bounds for length {}",
+                index, self.len
+            );
+        }
+        index % self.array.len()
+    }
+}
+
+impl<Arr> HasLength for Wrapping<Arr>
+where
+    Arr: HasLength,
+{
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<Arr> Index<usize> for Wrapping<Arr>
+where
+    Arr: Array,
+{
+    type Output = <Arr as Index<usize>>::Output;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.array[self.wrap_index(index)]
+    }
+}
+
+impl<Arr> IndexMut<usize> for Wrapping<Arr>
+where
+    Arr: ArrayMut,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let index = self.wrap_index(index);
+        &mut self.array[index]
+    }
+}
+
+impl<Arr> Array for Wrapping<Arr> where Arr: Array {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn wrapping() {
+        let vec: VecDeque<_> = vec![1, 2, 3].into();
+        let mut wrapped = Wrapping::new(vec, 7);
+        assert_eq!(7, wrapped.len());
+        assert_eq!(Some(&1), wrapped.get(3));
+        assert_eq!(Some(&1), wrapped.last());
+        assert_eq!(None, wrapped.get(7));
+        wrapped[4] = 5;
+        assert_eq!(&5, &wrapped.as_inner()[1]);
+        assert!(wrapped.starts_with(&[1, 5, 3, 1, 5]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn wrapping_out_of_bounds() {
+        let vec: VecDeque<_> = vec![1, 2, 3].into();
+        let wrapped = Wrapping::new(vec, 4);
+        let _ = wrapped[4];
+    }
+}